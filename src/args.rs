@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Parser, ValueEnum};
 
 /// Simple program to greet a person
@@ -18,6 +20,178 @@ pub struct Args {
 
     #[arg(short = 'c', long, value_enum, default_value = "complex")]
     pub scene: Scene,
+
+    /// Load the scene from a TOML file instead of a built-in scene
+    #[arg(short = 'f', long)]
+    pub scene_file: Option<PathBuf>,
+
+    /// Light transport algorithm
+    #[arg(long, value_enum, default_value = "mis")]
+    pub integrator: IntegratorKind,
+
+    /// Sample pattern for pixel, lens, time and bounce dimensions
+    #[arg(long, value_enum, default_value = "random")]
+    pub sampler: SamplerChoice,
+
+    /// Pixel reconstruction filter that weights each sample's contribution to nearby pixels
+    #[arg(long, value_enum, default_value = "box")]
+    pub filter: FilterChoice,
+
+    /// Filter radius in pixels [default: 0.5 for box, 1 for tent, 1.5 for gaussian,
+    /// 2 for mitchell and lanczos]
+    #[arg(long)]
+    pub filter_radius: Option<f64>,
+
+    /// How the top-level BVH chooses its split planes
+    #[arg(long, value_enum, default_value = "sah")]
+    pub bvh: BvhSplit,
+
+    /// Maximum number of primitives in a BVH leaf
+    #[arg(long, default_value = "4")]
+    pub bvh_leaf_size: usize,
+
+    /// Where to write the image; the format is inferred from the extension
+    /// [default: output/image.png, or the extension of --format]
+    #[arg(short = 'o', long)]
+    pub output: Option<PathBuf>,
+
+    /// Override the image format instead of inferring it from the output extension;
+    /// float formats store linear radiance without clamping
+    #[arg(long, value_enum)]
+    pub format: Option<ImageFormatKind>,
+
+    /// Tone curve applied before writing 8-bit formats
+    #[arg(long, value_enum, default_value = "clamp")]
+    pub tone_map: ToneMapKind,
+
+    /// Radiance mapped to pure white by the extended Reinhard curve
+    #[arg(long, default_value = "4.0")]
+    pub white_point: f64,
+
+    /// Exposure compensation in stops (EV) applied before tone mapping
+    #[arg(long, default_value = "0.0", allow_negative_numbers = true)]
+    pub exposure: f64,
+
+    /// Sample each pixel until its noise estimate drops below --noise-threshold
+    #[arg(long)]
+    pub adaptive: bool,
+
+    /// Samples every pixel takes before adaptive sampling may stop
+    #[arg(long, default_value = "16")]
+    pub min_samples: u32,
+
+    /// Upper bound on the samples of a pixel in adaptive mode
+    #[arg(long, default_value = "1024")]
+    pub max_samples: u32,
+
+    /// Relative standard error of a pixel's mean luminance at which it counts as converged
+    #[arg(long, default_value = "0.01")]
+    pub noise_threshold: f64,
+
+    /// Also write an image of the samples taken per pixel (adaptive mode only)
+    #[arg(long, requires = "adaptive")]
+    pub sample_heatmap: Option<PathBuf>,
+
+    /// Render in passes of this many samples per pixel, rewriting the image after each pass
+    /// and when interrupted with Ctrl-C
+    #[arg(long, conflicts_with = "adaptive")]
+    pub progressive: Option<u32>,
+
+    /// Periodically save the progressive render state to this file
+    #[arg(long, requires = "progressive")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Number of passes between checkpoints; one is also written when the render stops
+    #[arg(long, default_value = "1")]
+    pub checkpoint_interval: u32,
+
+    /// Continue a progressive render from a checkpoint taken with the same scene and settings
+    #[arg(long, requires = "progressive")]
+    pub resume: Option<PathBuf>,
+
+    /// Seed for all random numbers; renders with the same seed and settings are identical
+    #[arg(long, default_value = "0")]
+    pub seed: u64,
+
+    /// Color space of the written image
+    #[arg(long, value_enum, default_value = "srgb")]
+    pub color_space: ColorSpaceKind,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum IntegratorKind {
+    /// Material sampling only
+    Path,
+    /// Light and material sampling combined with multiple importance sampling
+    Mis,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ImageFormatKind {
+    Png,
+    Jpeg,
+    Bmp,
+    Tiff,
+    /// OpenEXR, 32-bit float
+    Exr,
+    /// Radiance RGBE
+    Hdr,
+    /// Portable float map
+    Pfm,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ToneMapKind {
+    /// Clip values above 1
+    Clamp,
+    Reinhard,
+    /// Reinhard with a white point, see --white-point
+    ReinhardExtended,
+    /// ACES filmic approximation
+    Aces,
+    /// Uncharted 2 filmic curve
+    Hable,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ColorSpaceKind {
+    /// Rec.709 primaries, sRGB transfer curve
+    Srgb,
+    /// Rec.709 primaries, no transfer curve
+    LinearRec709,
+    /// P3 primaries, sRGB transfer curve
+    DisplayP3,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum SamplerChoice {
+    /// Independent uniform random numbers
+    Random,
+    /// Jittered strata, shuffled per dimension
+    Stratified,
+    /// Randomised Halton sequence
+    Halton,
+    /// Owen-scrambled Sobol sequence
+    Sobol,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum FilterChoice {
+    Box,
+    Tent,
+    Gaussian,
+    /// Mitchell-Netravali cubic (B = C = 1/3)
+    Mitchell,
+    /// Lanczos windowed sinc
+    Lanczos,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum BvhSplit {
+    /// Median of the primitives along the longest axis; leaf size 1 matches older builds
+    Median,
+    /// Surface area heuristic over binned candidate planes
+    Sah,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
@@ -29,5 +203,9 @@ pub enum Scene {
     Quads,
     SimpleLight,
     CornellBox,
+    CornellSmoke,
     Transformation,
+    Triangles,
+    Instances,
+    MotionBlur,
 }
@@ -1,14 +1,15 @@
 use crate::{
+    film::Film,
     interval::Interval,
     material::MaterialInteractResult,
-    object::Hittable,
+    object::{Hittable, HittableList},
     ray::Ray,
-    utils::{rand_double, rand_vector_in_unit_disk},
+    sampler::{sample_uniform_disk, Sampler, SamplerKind},
+    shutter::Shutter,
+    utils::reseed,
     vec3::{Color, Point, Vec3},
 };
 
-const WHITE: Color = Color::new(1.0, 1.0, 1.0);
-const BLUE: Color = Color::new(0.5, 0.7, 1.0);
 const BLACK: Color = Color::zero();
 
 const RAY_INTERVAL: Interval = Interval {
@@ -16,11 +17,41 @@ const RAY_INTERVAL: Interval = Interval {
     max: f64::INFINITY,
 };
 
+/// Light transport algorithm used to estimate the color of each camera ray.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    /// Follow material scattering only; light is found when a bounce happens to hit it.
+    Path,
+    /// Also sample emitters directly at every diffuse bounce, weighting both strategies with
+    /// multiple importance sampling.
+    Mis,
+}
+
+/// Stopping rule for adaptive sampling: every pixel takes between `min_samples` and
+/// `max_samples`, stopping once its estimate is below the noise threshold.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveSampling {
+    pub min_samples: u32,
+    pub max_samples: u32,
+    /// Standard error of the mean luminance, relative to the mean, at which a pixel is converged
+    pub noise_threshold: f64,
+}
+
+/// Luminance below which the noise threshold is applied as an absolute error, so that dark
+/// pixels are not sampled forever.
+const ADAPTIVE_MIN_LUMINANCE: f64 = 0.01;
+
 pub struct OutputQuality {
     pub image_width: u32,
     pub image_height: u32,
     pub samples_per_pixel: u32,
     pub max_depth: u32,
+    pub integrator: Integrator,
+    pub sampler: SamplerKind,
+    /// Replaces the fixed `samples_per_pixel` with a per-pixel adaptive count
+    pub adaptive: Option<AdaptiveSampling>,
+    /// Seed for all random numbers drawn while rendering
+    pub seed: u64,
 }
 
 pub struct CameraOption {
@@ -34,9 +65,10 @@ pub struct CameraOption {
     pub focus_distance: f64,
     pub quality: OutputQuality,
     pub bg_color: Color,
+    pub shutter: Shutter,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Camera {
     // Basis vector
     position: Point,
@@ -45,15 +77,21 @@ pub struct Camera {
     pixel_delta_u: Vec3,
     pixel_delta_v: Vec3,
 
+    image_width: u32,
+    image_height: u32,
     samples_per_pixel: u32,
-    pixel_samples_scale: f64,
     max_depth: u32,
+    integrator: Integrator,
+    sampler: SamplerKind,
+    adaptive: Option<AdaptiveSampling>,
+    seed: u64,
 
     defocus_angle: f64,
 
     defocus_disk_u: Vec3,
     defocus_disk_v: Vec3,
     bg_color: Color,
+    shutter: Shutter,
 }
 
 impl Camera {
@@ -63,6 +101,10 @@ impl Camera {
             image_height,
             samples_per_pixel,
             max_depth,
+            integrator,
+            sampler,
+            adaptive,
+            seed,
         } = opt.quality;
         let h = (opt.vfov.to_radians() / 2.0).tan();
 
@@ -83,8 +125,6 @@ impl Camera {
             opt.look_from - opt.focus_distance * w - viewport_u / 2.0 - viewport_v / 2.0;
         let pixel00_loc = viewport_upper_left + (pixel_delta_u + pixel_delta_v) * 0.5;
 
-        let pixel_samples_scale = 1.0 / samples_per_pixel as f64;
-
         let defocus_radius = opt.focus_distance * (opt.defocus_angle / 2.0).to_radians().tan();
         let defocus_disk_u = u * defocus_radius;
         let defocus_disk_v = v * defocus_radius;
@@ -97,24 +137,41 @@ impl Camera {
             pixel_delta_u,
             pixel_delta_v,
 
+            image_width,
+            image_height,
             samples_per_pixel,
-            pixel_samples_scale,
             max_depth,
+            integrator,
+            sampler,
+            adaptive,
+            seed,
 
             defocus_disk_u,
             defocus_disk_v,
             defocus_angle: opt.defocus_angle,
+            shutter: opt.shutter,
         }
     }
 
-    fn ray_color<T: Hittable>(&self, object: &T, ray: &Ray, depth: u32) -> Color {
+    /// Width and height of the rendered image in pixels.
+    pub fn image_size(&self) -> (u32, u32) {
+        (self.image_width, self.image_height)
+    }
+
+    fn ray_color<T: Hittable>(
+        &self,
+        object: &T,
+        ray: &Ray,
+        depth: u32,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
         if depth == 0 {
             return BLACK;
         }
-        if let Some(record) = object.hit(ray, &RAY_INTERVAL) {
-            return match record.material.interact(ray, &record) {
+        if let Some(record) = object.hit(ray, &RAY_INTERVAL, sampler) {
+            return match record.material.interact(ray, &record, sampler) {
                 MaterialInteractResult::Scatter { attenuation, ray } => {
-                    attenuation * self.ray_color(object, &ray, depth - 1)
+                    attenuation * self.ray_color(object, &ray, depth - 1, sampler)
                 }
                 MaterialInteractResult::Emitted { color } => color,
                 MaterialInteractResult::None => Color::BLACK,
@@ -124,43 +181,211 @@ impl Camera {
         self.bg_color
     }
 
-    pub fn project_ray<T: Hittable>(&self, i: u32, j: u32, world: &T) -> Color {
-        let mut color = Color::zero();
-        for _sample in 0..self.samples_per_pixel {
-            let ray = self.get_ray(i, j);
-            color += self.ray_color(world, &ray, self.max_depth);
+    /// Power heuristic weight for a sample drawn with density `pdf_a` that strategy `b` could also produce.
+    fn mis_weight(pdf_a: f64, pdf_b: f64) -> f64 {
+        let (a2, b2) = (pdf_a * pdf_a, pdf_b * pdf_b);
+        if a2 + b2 == 0.0 {
+            return 0.0;
         }
-        color * self.pixel_samples_scale
+        a2 / (a2 + b2)
     }
 
-    fn get_ray(&self, i: u32, j: u32) -> Ray {
-        let offset = Self::sample_square();
+    /// Estimates the light arriving along `ray` by combining material sampling with direct
+    /// sampling of `lights`, weighted by multiple importance sampling.
+    fn ray_color_mis<T: Hittable>(
+        &self,
+        world: &T,
+        lights: &HittableList,
+        ray: &Ray,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let has_lights = !lights.objects().is_empty();
+        let mut color = BLACK;
+        let mut throughput = Color::WHITE;
+        let mut ray = *ray;
+        // density of the material sample that produced `ray`, if it could also have been light sampled
+        let mut scatter_pdf: Option<f64> = None;
+
+        for _depth in 0..self.max_depth {
+            let Some(record) = world.hit(&ray, &RAY_INTERVAL, sampler) else {
+                color += throughput * self.bg_color;
+                break;
+            };
+
+            if record.material.is_emissive() {
+                let weight = match scatter_pdf {
+                    Some(pdf) => Self::mis_weight(pdf, lights.pdf_value(&ray.origin, &ray.dir)),
+                    None => 1.0,
+                };
+                color += throughput * record.material.emitted(&record) * weight;
+                break;
+            }
+
+            let wo = -ray.dir.to_unit();
+            let Some(sample) = record.material.sample(&record, &wo, sampler) else {
+                break;
+            };
+            scatter_pdf =
+                (!sample.is_specular && sample.pdf > 0.0 && has_lights).then_some(sample.pdf);
+
+            if scatter_pdf.is_some() {
+                let light_ray = Ray {
+                    origin: record.point,
+                    dir: lights.random_direction(&record.point, sampler),
+                    time: ray.time,
+                };
+                let light_pdf = lights.pdf_value(&light_ray.origin, &light_ray.dir);
+                if light_pdf > 0.0 {
+                    if let Some(light_record) = world.hit(&light_ray, &RAY_INTERVAL, sampler) {
+                        if light_record.material.is_emissive() {
+                            let wi = light_ray.dir.to_unit();
+                            let scattering = record.material.eval(&record, &wi, &wo);
+                            let material_pdf = record.material.pdf(&record, &wi, &wo);
+                            let weight = Self::mis_weight(light_pdf, material_pdf);
+                            color += throughput
+                                * scattering
+                                * light_record.material.emitted(&light_record)
+                                * (weight / light_pdf);
+                        }
+                    }
+                }
+            }
+
+            throughput = throughput * sample.weight;
+            ray = Ray {
+                origin: record.point,
+                dir: sample.direction,
+                time: ray.time,
+            };
+        }
+        color
+    }
+
+    /// Renders pixel (`i`, `j`) into `film`, with the fixed sample count or adaptively, and
+    /// returns the number of samples taken.
+    pub fn render_pixel<T: Hittable>(
+        &self,
+        i: u32,
+        j: u32,
+        world: &T,
+        lights: &HittableList,
+        film: &mut Film,
+    ) -> u32 {
+        if let Some(adaptive) = self.adaptive {
+            return self.render_pixel_adaptive(i, j, world, lights, &adaptive, film);
+        }
+        self.render_samples(i, j, world, lights, 0, self.samples_per_pixel, film);
+        self.samples_per_pixel
+    }
+
+    /// Splats samples `first_sample..first_sample + samples` of pixel (`i`, `j`) into `film`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_samples<T: Hittable>(
+        &self,
+        i: u32,
+        j: u32,
+        world: &T,
+        lights: &HittableList,
+        first_sample: u32,
+        samples: u32,
+        film: &mut Film,
+    ) {
+        for sample in first_sample..first_sample + samples {
+            let (offset, color) = self.sample_pixel(i, j, sample, world, lights);
+            film.add_sample(i, j, offset, color);
+        }
+    }
+
+    /// Samples until the running variance of the pixel luminance (Welford's algorithm) says
+    /// the mean is accurate enough.
+    fn render_pixel_adaptive<T: Hittable>(
+        &self,
+        i: u32,
+        j: u32,
+        world: &T,
+        lights: &HittableList,
+        adaptive: &AdaptiveSampling,
+        film: &mut Film,
+    ) -> u32 {
+        let mut mean = 0.0;
+        let mut m2 = 0.0;
+        let mut samples = 0;
+
+        while samples < adaptive.max_samples {
+            let (offset, color) = self.sample_pixel(i, j, samples, world, lights);
+            film.add_sample(i, j, offset, color);
+            samples += 1;
+
+            let luminance = color.luminance();
+            let delta = luminance - mean;
+            mean += delta / samples as f64;
+            m2 += delta * (luminance - mean);
+
+            if samples >= adaptive.min_samples.max(2) {
+                let variance = m2 / (samples - 1) as f64;
+                let standard_error = (variance / samples as f64).sqrt();
+                if standard_error <= adaptive.noise_threshold * mean.max(ADAPTIVE_MIN_LUMINANCE) {
+                    break;
+                }
+            }
+        }
+        samples
+    }
+
+    /// Traces sample number `sample` of pixel (`i`, `j`), returning its offset from the pixel
+    /// center and its radiance. Its random numbers depend only on the seed and these indices,
+    /// so the image is the same however pixels are scheduled.
+    fn sample_pixel<T: Hittable>(
+        &self,
+        i: u32,
+        j: u32,
+        sample: u32,
+        world: &T,
+        lights: &HittableList,
+    ) -> ((f64, f64), Color) {
+        reseed(self.seed, &[i as u64, j as u64, sample as u64]);
+        let samples_per_pixel = self
+            .adaptive
+            .map_or(self.samples_per_pixel, |adaptive| adaptive.max_samples);
+        let mut sampler = self
+            .sampler
+            .create(self.seed, i, j, sample, samples_per_pixel);
+        let sampler = sampler.as_mut();
+        let (dx, dy) = sampler.get_2d();
+        let offset = (dx - 0.5, dy - 0.5);
+        let ray = self.get_ray(i, j, offset, sampler);
+        let color = match self.integrator {
+            Integrator::Path => self.ray_color(world, &ray, self.max_depth, sampler),
+            Integrator::Mis => self.ray_color_mis(world, lights, &ray, sampler),
+        };
+        (offset, color)
+    }
+
+    /// Builds the camera ray through `offset` from the center of pixel (`i`, `j`), reading
+    /// the lens position and time from the next three sampler dimensions. The time follows
+    /// the shutter, which may open later for lower rows.
+    fn get_ray(&self, i: u32, j: u32, offset: (f64, f64), sampler: &mut dyn Sampler) -> Ray {
         let pixel_sample = self.pixel00_loc
-            + (i as f64 + offset.x) * self.pixel_delta_u
-            + (j as f64 + offset.y) * self.pixel_delta_v;
+            + (i as f64 + offset.0) * self.pixel_delta_u
+            + (j as f64 + offset.1) * self.pixel_delta_v;
+        let lens = sampler.get_2d();
         let ray_origin = if self.defocus_angle <= 0.0 {
             self.position
         } else {
-            self.sample_defocus_disk()
+            self.sample_defocus_disk(lens)
         };
 
         Ray {
             origin: ray_origin,
             dir: pixel_sample - ray_origin,
-            time: rand_double(),
-        }
-    }
-
-    fn sample_square() -> Vec3 {
-        Vec3 {
-            x: rand_double() - 0.5,
-            y: rand_double() - 0.5,
-            z: 0.0,
+            time: self
+                .shutter
+                .sample_time(sampler.get_1d(), j, self.image_height),
         }
     }
 
-    fn sample_defocus_disk(&self) -> Point {
-        let p = rand_vector_in_unit_disk();
+    fn sample_defocus_disk(&self, u: (f64, f64)) -> Point {
+        let p = sample_uniform_disk(u);
         self.position + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
     }
 }
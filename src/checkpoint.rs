@@ -0,0 +1,278 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{film::Film, filter::Filter, vec3::Color};
+
+/// Bumped whenever the checkpoint layout changes.
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// Everything that must be identical for the samples in a checkpoint to be combined with new ones.
+///
+/// No generator state is saved: the random numbers of a sample are derived from `seed`, its
+/// pixel and its index within the pixel, so the seed and the per-pixel sample counts of the
+/// checkpoint are enough for a resumed render to continue exactly where it stopped.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RenderSettings {
+    /// Name of the built-in scene, or the scene file path with a hash of its contents
+    pub scene: String,
+    pub image_width: u32,
+    pub image_height: u32,
+    pub max_depth: u32,
+    pub integrator: String,
+    pub sampler: String,
+    /// Stratified sampling shuffles its strata in rounds of this many samples
+    pub samples_per_pixel: u32,
+    /// Adaptive sampling thresholds, if any
+    pub adaptive: String,
+    /// Samples per pixel added by each progressive pass
+    pub pass_samples: u32,
+    /// Reconstruction filter and radius
+    pub filter: String,
+    pub seed: u64,
+}
+
+/// A progressive render stopped between passes, or part-way through one.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    version: u32,
+    settings: RenderSettings,
+    /// Passes finished so far; pixels reached by a cancelled pass may hold some of its samples
+    passes_completed: u32,
+    /// Filter-weighted radiance sums and the sums of their weights
+    radiance: Vec<[f64; 3]>,
+    weights: Vec<f64>,
+    /// Samples taken per pixel, which is also where each pixel's random sequence continues
+    samples: Vec<u32>,
+}
+
+impl Checkpoint {
+    pub fn new(settings: RenderSettings, passes_completed: u32, film: &Film) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            settings,
+            passes_completed,
+            radiance: film.radiance().iter().map(|c| [c.x, c.y, c.z]).collect(),
+            weights: film.weights().to_vec(),
+            samples: film.samples().to_vec(),
+        }
+    }
+
+    /// Writes to a temporary file first so an interrupted write never destroys the previous
+    /// checkpoint.
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let bytes = bincode::serialize(self)
+            .map_err(|err| CheckpointError::new(path, format!("cannot encode: {err}")))?;
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, bytes)
+            .and_then(|_| fs::rename(&temp_path, path))
+            .map_err(|err| CheckpointError::new(path, format!("cannot write: {err}")))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CheckpointError> {
+        let bytes = fs::read(path)
+            .map_err(|err| CheckpointError::new(path, format!("cannot read: {err}")))?;
+        let checkpoint: Self = bincode::deserialize(&bytes)
+            .map_err(|err| CheckpointError::new(path, format!("not a valid checkpoint: {err}")))?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::new(
+                path,
+                format!(
+                    "checkpoint version {} is not supported (expected {CHECKPOINT_VERSION})",
+                    checkpoint.version
+                ),
+            ));
+        }
+        let pixel_count =
+            (checkpoint.settings.image_width * checkpoint.settings.image_height) as usize;
+        if checkpoint.radiance.len() != pixel_count
+            || checkpoint.weights.len() != pixel_count
+            || checkpoint.samples.len() != pixel_count
+        {
+            return Err(CheckpointError::new(
+                path,
+                "pixel buffers do not match the image size",
+            ));
+        }
+        Ok(checkpoint)
+    }
+
+    /// Checks that the checkpoint was taken with `settings`, naming the first mismatch.
+    pub fn validate(&self, path: &Path, settings: &RenderSettings) -> Result<(), CheckpointError> {
+        let saved = &self.settings;
+        let mismatches = [
+            ("scene", saved.scene.clone(), settings.scene.clone()),
+            (
+                "image size",
+                format!("{}x{}", saved.image_width, saved.image_height),
+                format!("{}x{}", settings.image_width, settings.image_height),
+            ),
+            (
+                "depth",
+                saved.max_depth.to_string(),
+                settings.max_depth.to_string(),
+            ),
+            (
+                "integrator",
+                saved.integrator.clone(),
+                settings.integrator.clone(),
+            ),
+            ("sampler", saved.sampler.clone(), settings.sampler.clone()),
+            (
+                "samples per pixel",
+                saved.samples_per_pixel.to_string(),
+                settings.samples_per_pixel.to_string(),
+            ),
+            (
+                "adaptive sampling",
+                saved.adaptive.clone(),
+                settings.adaptive.clone(),
+            ),
+            (
+                "pass size",
+                saved.pass_samples.to_string(),
+                settings.pass_samples.to_string(),
+            ),
+            ("filter", saved.filter.clone(), settings.filter.clone()),
+            ("seed", saved.seed.to_string(), settings.seed.to_string()),
+        ];
+        for (name, saved, current) in mismatches {
+            if saved != current {
+                return Err(CheckpointError::new(
+                    path,
+                    format!("{name} does not match: checkpoint has `{saved}`, current render has `{current}`"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn passes_completed(&self) -> u32 {
+        self.passes_completed
+    }
+
+    /// Rebuilds the film; `filter` must be the one described by the validated settings.
+    pub fn into_film(self, filter: Filter) -> Film {
+        let radiance = self
+            .radiance
+            .into_iter()
+            .map(|[r, g, b]| Color::new(r, g, b))
+            .collect();
+        Film::from_parts(
+            self.settings.image_width,
+            filter,
+            radiance,
+            self.weights,
+            self.samples,
+        )
+    }
+}
+
+/// Stable 64-bit FNV-1a hash, used to recognise a scene file whose contents changed.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// An error raised while reading, writing or validating a checkpoint file.
+#[derive(Debug)]
+pub struct CheckpointError {
+    path: PathBuf,
+    message: String,
+}
+
+impl CheckpointError {
+    pub fn new(path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::{
+        film::Film,
+        filter::{Filter, FilterKind},
+        vec3::Color,
+    };
+
+    use super::{Checkpoint, RenderSettings};
+
+    fn settings() -> RenderSettings {
+        RenderSettings {
+            scene: "CornellBox".to_string(),
+            image_width: 2,
+            image_height: 1,
+            max_depth: 10,
+            integrator: "Mis".to_string(),
+            sampler: "Stratified".to_string(),
+            samples_per_pixel: 16,
+            adaptive: "None".to_string(),
+            pass_samples: 4,
+            filter: format!("{:?}", filter()),
+            seed: 7,
+        }
+    }
+
+    fn filter() -> Filter {
+        Filter::new(FilterKind::Box, 0.5)
+    }
+
+    #[test]
+    fn round_trips_and_rejects_other_settings() {
+        let mut film = Film::new(2, 1, filter());
+        for _ in 0..4 {
+            film.add_sample(1, 0, (0.0, 0.0), Color::new(1.0, 2.0, 3.0));
+        }
+        let path = env::temp_dir().join(format!("checkpoint-test-{}.bin", std::process::id()));
+        Checkpoint::new(settings(), 3, &film).save(&path).unwrap();
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(checkpoint.validate(&path, &settings()).is_ok());
+        let other = RenderSettings {
+            max_depth: 5,
+            ..settings()
+        };
+        assert!(checkpoint.validate(&path, &other).is_err());
+
+        let reseeded = RenderSettings {
+            seed: 8,
+            ..settings()
+        };
+        let err = checkpoint.validate(&path, &reseeded).unwrap_err();
+        assert!(err.to_string().contains("seed does not match"));
+        let resampled = RenderSettings {
+            sampler: "Sobol".to_string(),
+            ..settings()
+        };
+        assert!(checkpoint.validate(&path, &resampled).is_err());
+        let longer_passes = RenderSettings {
+            pass_samples: 8,
+            ..settings()
+        };
+        assert!(checkpoint.validate(&path, &longer_passes).is_err());
+
+        assert_eq!(checkpoint.passes_completed(), 3);
+        let film = checkpoint.into_film(filter());
+        assert_eq!(film.samples(), &[0, 4]);
+        assert_eq!(film.resolve()[1].y, 2.0);
+    }
+}
@@ -0,0 +1,5 @@
+mod space;
+mod transfer;
+
+pub use space::ColorSpace;
+pub use transfer::TransferFunction;
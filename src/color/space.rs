@@ -0,0 +1,54 @@
+use crate::vec3::Color;
+
+use super::TransferFunction;
+
+/// Linear Rec.709 to linear Display P3, both with a D65 white point.
+const REC709_TO_P3: [[f64; 3]; 3] = [
+    [0.822_462, 0.177_538, 0.0],
+    [0.033_194, 0.966_806, 0.0],
+    [0.017_083, 0.072_397, 0.910_520],
+];
+
+/// Color space of the written image. Radiance is computed in linear Rec.709.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum ColorSpace {
+    /// Rec.709 primaries with the sRGB transfer curve
+    #[default]
+    Srgb,
+    /// Rec.709 primaries without a transfer curve
+    LinearRec709,
+    /// DCI-P3 primaries, D65 white, sRGB transfer curve
+    DisplayP3,
+}
+
+impl ColorSpace {
+    pub fn transfer_function(self) -> TransferFunction {
+        match self {
+            ColorSpace::Srgb | ColorSpace::DisplayP3 => TransferFunction::Srgb,
+            ColorSpace::LinearRec709 => TransferFunction::Linear,
+        }
+    }
+
+    /// Converts linear Rec.709 radiance to linear values in this space's primaries.
+    pub fn convert_primaries(self, color: Color) -> Color {
+        match self {
+            ColorSpace::Srgb | ColorSpace::LinearRec709 => color,
+            ColorSpace::DisplayP3 => {
+                let [r, g, b] =
+                    REC709_TO_P3.map(|row| row[0] * color.x + row[1] * color.y + row[2] * color.z);
+                Color::new(r, g, b)
+            }
+        }
+    }
+
+    /// Converts linear Rec.709 radiance to display values in this space.
+    pub fn encode(self, color: Color) -> Color {
+        let color = self.convert_primaries(color);
+        let transfer = self.transfer_function();
+        Color::new(
+            transfer.encode(color.x),
+            transfer.encode(color.y),
+            transfer.encode(color.z),
+        )
+    }
+}
@@ -0,0 +1,56 @@
+/// Encoding between linear light and the values stored in an image.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TransferFunction {
+    /// Values are stored as linear light, as in data textures or linear outputs
+    Linear,
+    /// The piecewise IEC 61966-2-1 curve, also used by Display P3
+    Srgb,
+}
+
+impl TransferFunction {
+    /// Linear light to stored value.
+    pub fn encode(self, linear: f64) -> f64 {
+        match self {
+            TransferFunction::Linear => linear,
+            TransferFunction::Srgb => {
+                if linear <= 0.0031308 {
+                    12.92 * linear
+                } else {
+                    1.055 * linear.powf(1.0 / 2.4) - 0.055
+                }
+            }
+        }
+    }
+
+    /// Stored value to linear light.
+    pub fn decode(self, encoded: f64) -> f64 {
+        match self {
+            TransferFunction::Linear => encoded,
+            TransferFunction::Srgb => {
+                if encoded <= 0.04045 {
+                    encoded / 12.92
+                } else {
+                    ((encoded + 0.055) / 1.055).powf(2.4)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransferFunction;
+
+    #[test]
+    fn srgb_round_trips_and_matches_reference_points() {
+        let srgb = TransferFunction::Srgb;
+        for i in 0..=255 {
+            let encoded = i as f64 / 255.0;
+            assert!((srgb.encode(srgb.decode(encoded)) - encoded).abs() < 1e-9);
+        }
+        // mid grey in sRGB is about 21.4% linear light
+        assert!((srgb.decode(0.5) - 0.214).abs() < 1e-3);
+        assert_eq!(srgb.encode(0.0), 0.0);
+        assert!((srgb.encode(1.0) - 1.0).abs() < 1e-12);
+    }
+}
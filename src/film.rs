@@ -0,0 +1,176 @@
+use std::ops::Range;
+
+use crate::{filter::Filter, vec3::Color};
+
+/// Filter-weighted sums of radiance samples, for images built up over several passes.
+///
+/// A film may cover only a band of image rows (see `Film::tile`), so that rows can be
+/// rendered in parallel and merged back in a fixed order.
+pub struct Film {
+    width: u32,
+    height: u32,
+    rows: Range<u32>,
+    filter: Filter,
+    radiance: Vec<Color>,
+    weights: Vec<f64>,
+    /// Samples taken through each pixel, regardless of where the filter spread them
+    samples: Vec<u32>,
+}
+
+impl Film {
+    pub fn new(width: u32, height: u32, filter: Filter) -> Self {
+        Self::with_rows(width, height, 0..height, filter)
+    }
+
+    fn with_rows(width: u32, height: u32, rows: Range<u32>, filter: Filter) -> Self {
+        let pixel_count = (width * rows.len() as u32) as usize;
+        Self {
+            width,
+            height,
+            rows,
+            filter,
+            radiance: vec![Color::BLACK; pixel_count],
+            weights: vec![0.0; pixel_count],
+            samples: vec![0; pixel_count],
+        }
+    }
+
+    /// Rebuilds a film from the buffers of a `Checkpoint`.
+    pub fn from_parts(
+        width: u32,
+        filter: Filter,
+        radiance: Vec<Color>,
+        weights: Vec<f64>,
+        samples: Vec<u32>,
+    ) -> Self {
+        assert_eq!(radiance.len(), samples.len());
+        assert_eq!(weights.len(), samples.len());
+        let height = samples.len() as u32 / width;
+        Self {
+            width,
+            height,
+            rows: 0..height,
+            filter,
+            radiance,
+            weights,
+            samples,
+        }
+    }
+
+    /// An empty film for the samples of pixel rows `rows`, large enough to hold everything
+    /// the filter spreads into neighbouring rows.
+    pub fn tile(&self, rows: Range<u32>) -> Self {
+        let reach = self.filter.radius.ceil() as u32;
+        let start = rows.start.saturating_sub(reach);
+        let end = (rows.end + reach).min(self.height);
+        Self::with_rows(self.width, self.height, start..end, self.filter)
+    }
+
+    /// Adds every pixel of `tile` to this film.
+    pub fn merge(&mut self, tile: &Film) {
+        assert_eq!(self.width, tile.width);
+        let offset = ((tile.rows.start - self.rows.start) * self.width) as usize;
+        for (i, index) in (offset..offset + tile.samples.len()).enumerate() {
+            self.radiance[index] += tile.radiance[i];
+            self.weights[index] += tile.weights[i];
+            self.samples[index] += tile.samples[i];
+        }
+    }
+
+    /// Splats a sample taken through pixel (`x`, `y`) at `offset` from the pixel center into
+    /// every pixel the filter reaches.
+    pub fn add_sample(&mut self, x: u32, y: u32, offset: (f64, f64), radiance: Color) {
+        let px = x as f64 + offset.0;
+        let py = y as f64 + offset.1;
+        let radius = self.filter.radius;
+        let x_start = (px - radius).ceil().max(0.0) as u32;
+        let x_end = ((px + radius).floor() as i64 + 1).clamp(0, self.width as i64) as u32;
+        let y_start = ((py - radius).ceil().max(0.0) as u32).max(self.rows.start);
+        let y_end = ((py + radius).floor() as i64 + 1).clamp(0, self.rows.end as i64) as u32;
+
+        for ty in y_start..y_end {
+            for tx in x_start..x_end {
+                let weight = self.filter.weight(px - tx as f64, py - ty as f64);
+                if weight != 0.0 {
+                    let index = self.index(tx, ty);
+                    self.radiance[index] += radiance * weight;
+                    self.weights[index] += weight;
+                }
+            }
+        }
+        let index = self.index(x, y);
+        self.samples[index] += 1;
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        ((y - self.rows.start) * self.width + x) as usize
+    }
+
+    /// Smallest number of samples any pixel has received.
+    pub fn min_samples(&self) -> u32 {
+        self.samples.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Filtered radiance of every pixel in row-major order. Pixels no sample reached are
+    /// black, and the negative lobes of Mitchell and Lanczos are clamped to zero.
+    pub fn resolve(&self) -> Vec<Color> {
+        self.radiance
+            .iter()
+            .zip(&self.weights)
+            .map(|(&radiance, &weight)| {
+                if weight <= 0.0 {
+                    return Color::BLACK;
+                }
+                let color = radiance / weight;
+                Color::new(color.x.max(0.0), color.y.max(0.0), color.z.max(0.0))
+            })
+            .collect()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn radiance(&self) -> &[Color] {
+        &self.radiance
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    pub fn samples(&self) -> &[u32] {
+        &self.samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        filter::{Filter, FilterKind},
+        vec3::Color,
+    };
+
+    use super::Film;
+
+    #[test]
+    fn tiles_merge_into_the_same_image_as_direct_splatting() {
+        let filter = Filter::new(FilterKind::Tent, 1.0);
+        let mut direct = Film::new(3, 3, filter);
+        let mut merged = Film::new(3, 3, filter);
+        let samples = [(0, 0, (0.2, -0.4)), (1, 1, (-0.3, 0.3)), (2, 2, (0.1, 0.4))];
+        for (i, &(x, y, offset)) in samples.iter().enumerate() {
+            let color = Color::new(i as f64, 1.0, 2.0);
+            direct.add_sample(x, y, offset, color);
+            let mut tile = merged.tile(y..y + 1);
+            tile.add_sample(x, y, offset, color);
+            merged.merge(&tile);
+        }
+        for (a, b) in direct.resolve().iter().zip(merged.resolve()) {
+            assert_eq!((a.x, a.y, a.z), (b.x, b.y, b.z));
+        }
+        assert_eq!(direct.samples(), merged.samples());
+        // the centre pixel also collects a share of its neighbours' samples
+        assert!(direct.weights()[4] > 0.0 && direct.resolve()[4].x > 0.0);
+    }
+}
@@ -0,0 +1,118 @@
+use std::f64::consts::PI;
+
+/// Shape of a pixel reconstruction filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterKind {
+    Box,
+    Tent,
+    Gaussian,
+    /// Mitchell–Netravali with B = C = 1/3
+    Mitchell,
+    /// Windowed sinc with as many lobes as the radius
+    Lanczos,
+}
+
+impl FilterKind {
+    /// Radius used when none is given.
+    pub fn default_radius(self) -> f64 {
+        match self {
+            FilterKind::Box => 0.5,
+            FilterKind::Tent => 1.0,
+            FilterKind::Gaussian => 1.5,
+            FilterKind::Mitchell | FilterKind::Lanczos => 2.0,
+        }
+    }
+}
+
+/// A separable reconstruction filter. Every sample contributes to each pixel whose center
+/// lies within `radius` (in pixels) of it, weighted by the filter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Filter {
+    pub kind: FilterKind,
+    pub radius: f64,
+}
+
+/// One sample per pixel footprint, averaged uniformly.
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new(FilterKind::Box, FilterKind::Box.default_radius())
+    }
+}
+
+impl Filter {
+    pub fn new(kind: FilterKind, radius: f64) -> Self {
+        Self { kind, radius }
+    }
+
+    /// Weight of a sample at offset (`dx`, `dy`) from a pixel center.
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        self.weight_1d(dx) * self.weight_1d(dy)
+    }
+
+    fn weight_1d(&self, x: f64) -> f64 {
+        let x = x.abs();
+        let radius = self.radius;
+        if x >= radius {
+            return 0.0;
+        }
+        match self.kind {
+            FilterKind::Box => 1.0,
+            FilterKind::Tent => radius - x,
+            FilterKind::Gaussian => {
+                // shifted down so the curve reaches zero at the radius
+                let sigma = radius / 3.0;
+                let gaussian = |x: f64| (-x * x / (2.0 * sigma * sigma)).exp();
+                gaussian(x) - gaussian(radius)
+            }
+            FilterKind::Mitchell => mitchell(2.0 * x / radius),
+            FilterKind::Lanczos => sinc(x) * sinc(x / radius),
+        }
+    }
+}
+
+fn mitchell(x: f64) -> f64 {
+    const B: f64 = 1.0 / 3.0;
+    const C: f64 = 1.0 / 3.0;
+    let x2 = x * x;
+    let x3 = x2 * x;
+    let value = if x < 1.0 {
+        (12.0 - 9.0 * B - 6.0 * C) * x3 + (-18.0 + 12.0 * B + 6.0 * C) * x2 + (6.0 - 2.0 * B)
+    } else if x < 2.0 {
+        (-B - 6.0 * C) * x3
+            + (6.0 * B + 30.0 * C) * x2
+            + (-12.0 * B - 48.0 * C) * x
+            + (8.0 * B + 24.0 * C)
+    } else {
+        0.0
+    };
+    value / 6.0
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-5 {
+        return 1.0;
+    }
+    (PI * x).sin() / (PI * x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Filter, FilterKind};
+
+    #[test]
+    fn filters_peak_at_the_center_and_vanish_at_the_radius() {
+        for kind in [
+            FilterKind::Tent,
+            FilterKind::Gaussian,
+            FilterKind::Mitchell,
+            FilterKind::Lanczos,
+        ] {
+            let filter = Filter::new(kind, kind.default_radius());
+            let center = filter.weight(0.0, 0.0);
+            assert!(center > 0.0, "{kind:?}");
+            assert!(filter.weight(0.3, 0.1) < center, "{kind:?}");
+            assert!(filter.weight(filter.radius, 0.0).abs() < 1e-9, "{kind:?}");
+            assert_eq!(filter.weight(filter.radius + 0.1, 0.0), 0.0);
+        }
+    }
+}
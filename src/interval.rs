@@ -63,7 +63,7 @@ impl Interval {
         if x > self.max {
             return self.max;
         }
-        return x;
+        x
     }
 
     pub const ZERO: Interval = Interval::new(0.0, 0.0);
@@ -0,0 +1,73 @@
+//! A CPU path tracer after "Ray Tracing in One Weekend", usable as a library.
+//!
+//! Build a world from the primitives in [`object`] and the materials in [`material`], put it in
+//! a [`BVHTree`], and [`render`] it through a [`Camera`]:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use raytracing::{
+//!     camera::{CameraOption, Integrator, OutputQuality},
+//!     material::Lambertian,
+//!     object::{BVHOptions, Sphere},
+//!     sampler::SamplerKind,
+//!     shutter::Shutter,
+//!     render, BVHTree, Camera, Color, HittableList, Point, RenderOptions, Vec3,
+//! };
+//!
+//! let mut world = HittableList::empty();
+//! let ground = Arc::new(Lambertian::new_solid_color(Color::new(0.5, 0.5, 0.5)));
+//! world.add(Sphere::stationary(Point::new(0.0, -100.5, -1.0), 100.0, ground));
+//!
+//! let camera = Camera::new(CameraOption {
+//!     vfov: 90.0,
+//!     look_from: Point::new(0.0, 0.0, 0.0),
+//!     look_at: Point::new(0.0, 0.0, -1.0),
+//!     vup: Vec3::new(0.0, 1.0, 0.0),
+//!     defocus_angle: 0.0,
+//!     focus_distance: 1.0,
+//!     quality: OutputQuality {
+//!         image_width: 320,
+//!         image_height: 180,
+//!         samples_per_pixel: 32,
+//!         max_depth: 10,
+//!         integrator: Integrator::Mis,
+//!         sampler: SamplerKind::Random,
+//!         adaptive: None,
+//!         seed: 0,
+//!     },
+//!     bg_color: Color::new(0.7, 0.8, 1.0),
+//!     shutter: Shutter::default(),
+//! });
+//!
+//! let lights = world.lights();
+//! let world = BVHTree::build(world.objects(), BVHOptions::default());
+//! let image = render(&camera, &world, &lights, &RenderOptions::default());
+//! image.save("sphere.png").unwrap();
+//! ```
+
+pub mod camera;
+pub mod checkpoint;
+pub mod color;
+pub mod film;
+pub mod filter;
+pub mod interval;
+pub mod material;
+pub mod object;
+pub mod output;
+pub mod ray;
+pub mod render;
+pub mod sampler;
+pub mod scene;
+pub mod shutter;
+pub mod texture;
+pub mod utils;
+pub mod vec3;
+
+pub use camera::{Camera, CameraOption};
+pub use material::Material;
+pub use object::{BVHTree, Hittable, HittableList};
+pub use ray::Ray;
+pub use render::{render, RenderOptions};
+pub use texture::Texture;
+pub use vec3::{Color, Point, Vec3};
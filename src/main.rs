@@ -1,22 +1,25 @@
-use args::{Args, Scene};
-use camera::OutputQuality;
+use args::{
+    Args, BvhSplit, ColorSpaceKind, FilterChoice, ImageFormatKind, IntegratorKind, SamplerChoice,
+    Scene, ToneMapKind,
+};
 use clap::Parser;
-use image::ImageBuffer;
-use object::BVHTree;
-use rayon::prelude::*;
-use std::{sync::mpsc, thread, time::Instant};
-use vec3::Color;
+use image::ImageFormat;
+use raytracing::{
+    camera::{AdaptiveSampling, Integrator, OutputQuality},
+    checkpoint::{content_hash, Checkpoint, RenderSettings},
+    color::ColorSpace,
+    film::Film,
+    filter::{Filter, FilterKind},
+    object::{BVHOptions, Hittable, SplitMethod, Tlas},
+    output::{self, OutputFormat, ToneMapper, ToneMapping},
+    render::{CancellationToken, Renderer},
+    sampler::SamplerKind,
+    scene, utils,
+    vec3::Color,
+};
+use std::{fs, path::PathBuf, process, time::Instant};
 
 mod args;
-mod camera;
-mod interval;
-mod material;
-mod object;
-mod ray;
-mod scene;
-mod texture;
-mod utils;
-mod vec3;
 
 fn main() {
     let args = Args::parse();
@@ -28,6 +31,29 @@ fn main() {
         image_height,
         samples_per_pixel,
         scene,
+        scene_file,
+        integrator,
+        sampler,
+        filter,
+        filter_radius,
+        bvh,
+        bvh_leaf_size,
+        output,
+        format,
+        tone_map,
+        white_point,
+        exposure,
+        adaptive,
+        min_samples,
+        max_samples,
+        noise_threshold,
+        sample_heatmap,
+        progressive,
+        checkpoint,
+        checkpoint_interval,
+        resume,
+        seed,
+        color_space,
     } = args;
 
     let quality = OutputQuality {
@@ -35,58 +61,288 @@ fn main() {
         image_height,
         samples_per_pixel,
         max_depth: depth,
+        integrator: match integrator {
+            IntegratorKind::Path => Integrator::Path,
+            IntegratorKind::Mis => Integrator::Mis,
+        },
+        sampler: match sampler {
+            SamplerChoice::Random => SamplerKind::Random,
+            SamplerChoice::Stratified => SamplerKind::Stratified,
+            SamplerChoice::Halton => SamplerKind::Halton,
+            SamplerChoice::Sobol => SamplerKind::Sobol,
+        },
+        adaptive: adaptive.then_some(AdaptiveSampling {
+            min_samples,
+            max_samples,
+            noise_threshold,
+        }),
+        seed,
     };
 
-    let (world, camera) = match scene {
-        Scene::Complex => scene::construct_complex_scene(0.1, quality),
-        Scene::CheckeredSphere => scene::construct_checkered_sphere_scene(quality),
-        Scene::Earth => scene::construct_earth_scene(quality),
-        Scene::Perlin => scene::construct_perlin_spheres(quality),
-        Scene::Quads => scene::construct_quads_scene(quality),
-        Scene::SimpleLight => scene::construct_simple_light(quality),
-        Scene::CornellBox => scene::construct_cornell_box(quality),
-        Scene::Transformation => scene::construct_transformation_debug_scene(quality),
+    let filter_kind = match filter {
+        FilterChoice::Box => FilterKind::Box,
+        FilterChoice::Tent => FilterKind::Tent,
+        FilterChoice::Gaussian => FilterKind::Gaussian,
+        FilterChoice::Mitchell => FilterKind::Mitchell,
+        FilterChoice::Lanczos => FilterKind::Lanczos,
     };
+    let filter_radius = filter_radius.unwrap_or(filter_kind.default_radius());
+    if filter_radius <= 0.0 {
+        eprintln!("--filter-radius must be positive, got {filter_radius}");
+        process::exit(1);
+    }
+    let filter = Filter::new(filter_kind, filter_radius);
 
-    let world = BVHTree::from_list(world.objects());
-
-    let (tx, rx) = mpsc::channel::<(u32, u32, Color)>();
+    let format = format.map(|format| match format {
+        ImageFormatKind::Png => OutputFormat::Ldr(ImageFormat::Png),
+        ImageFormatKind::Jpeg => OutputFormat::Ldr(ImageFormat::Jpeg),
+        ImageFormatKind::Bmp => OutputFormat::Ldr(ImageFormat::Bmp),
+        ImageFormatKind::Tiff => OutputFormat::Ldr(ImageFormat::Tiff),
+        ImageFormatKind::Exr => OutputFormat::Exr,
+        ImageFormatKind::Hdr => OutputFormat::Hdr,
+        ImageFormatKind::Pfm => OutputFormat::Pfm,
+    });
+    let output = output.unwrap_or_else(|| {
+        let extension = format.map_or("png", OutputFormat::extension);
+        PathBuf::from("output/image").with_extension(extension)
+    });
+    // resolve the format before rendering so a bad extension fails fast
+    let format = format.unwrap_or_else(|| {
+        OutputFormat::from_path(&output).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            process::exit(1);
+        })
+    });
 
-    let total_pixel = image_height * image_width;
+    if white_point <= 0.0 {
+        eprintln!("--white-point must be positive, got {white_point}");
+        process::exit(1);
+    }
+    let tone_mapping = ToneMapping {
+        mapper: match tone_map {
+            ToneMapKind::Clamp => ToneMapper::Clamp,
+            ToneMapKind::Reinhard => ToneMapper::Reinhard,
+            ToneMapKind::ReinhardExtended => ToneMapper::ReinhardExtended { white_point },
+            ToneMapKind::Aces => ToneMapper::Aces,
+            ToneMapKind::Hable => ToneMapper::Hable,
+        },
+        exposure,
+    };
+    let color_space = match color_space {
+        ColorSpaceKind::Srgb => ColorSpace::Srgb,
+        ColorSpaceKind::LinearRec709 => ColorSpace::LinearRec709,
+        ColorSpaceKind::DisplayP3 => ColorSpace::DisplayP3,
+    };
 
-    let handle = thread::spawn(move || {
-        let mut img = ImageBuffer::new(image_width, image_height);
-        for (idx, (x, y, color)) in rx.iter().enumerate() {
-            if idx % 1000 == 999 {
-                let duration = Instant::now().duration_since(start);
-                eprint!(
-                    "\rProcessed: {}/{} pixels. Elapsed: {}s             ",
-                    idx + 1,
-                    total_pixel,
-                    duration.as_secs_f64()
-                );
+    let settings = RenderSettings {
+        scene: match &scene_file {
+            Some(path) => {
+                let contents = fs::read(path).unwrap_or_default();
+                format!("{} ({:016x})", path.display(), content_hash(&contents))
             }
-            img.put_pixel(x, y, color.to_rgb());
-        }
-        img.save("./output/image.png").expect("cannot write image");
-    });
+            None => format!("{scene:?}"),
+        },
+        image_width,
+        image_height,
+        max_depth: depth,
+        integrator: format!("{integrator:?}"),
+        sampler: format!("{sampler:?}"),
+        samples_per_pixel,
+        adaptive: format!("{:?}", quality.adaptive),
+        pass_samples: progressive.map_or(0, |pass_samples| pass_samples.max(1)),
+        filter: format!("{filter:?}"),
+        seed,
+    };
 
-    // Create iterator over all pixels
-    (0..total_pixel)
-        .into_par_iter() // Convert to parallel iterator
-        .for_each(|i| {
-            let x = i % image_width;
-            let y = i / image_width;
+    // scenes draw from their own stream, separate from the per-sample ones used while rendering
+    utils::reseed(seed, &[]);
+    let (world, camera) = match scene_file {
+        Some(path) => scene::load_scene_file(&path, quality).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            process::exit(1);
+        }),
+        None => match scene {
+            Scene::Complex => scene::construct_complex_scene(0.1, quality),
+            Scene::CheckeredSphere => scene::construct_checkered_sphere_scene(quality),
+            Scene::Earth => scene::construct_earth_scene(quality),
+            Scene::Perlin => scene::construct_perlin_spheres(quality),
+            Scene::Quads => scene::construct_quads_scene(quality),
+            Scene::SimpleLight => scene::construct_simple_light(quality),
+            Scene::CornellBox => scene::construct_cornell_box(quality),
+            Scene::CornellSmoke => scene::construct_cornell_smoke(quality),
+            Scene::Transformation => scene::construct_transformation_debug_scene(quality),
+            Scene::Triangles => scene::construct_triangles_scene(quality),
+            Scene::Instances => scene::construct_instances_scene(quality),
+            Scene::MotionBlur => scene::construct_motion_blur_scene(quality),
+        },
+    };
 
-            let color = camera.project_ray(x, y, &world);
-            tx.send((x, y, color)).expect("cannot notify progress");
-        });
+    let bvh_options = BVHOptions {
+        split: match bvh {
+            BvhSplit::Median => SplitMethod::Median,
+            BvhSplit::Sah => SplitMethod::Sah,
+        },
+        max_leaf_size: bvh_leaf_size,
+    };
+    let lights = world.lights();
+    let build_start = Instant::now();
+    let world = Tlas::build(world.objects(), bvh_options);
+    eprintln!(
+        "BVH build ({:?}, leaf size {}): {}s",
+        bvh,
+        bvh_leaf_size,
+        build_start.elapsed().as_secs_f64()
+    );
+    let render_start = Instant::now();
+    let save = |pixels: &[Color]| {
+        let result = output::save_image(
+            &output,
+            format,
+            image_width,
+            image_height,
+            pixels,
+            &tone_mapping,
+            color_space,
+        );
+        if let Err(err) = result {
+            eprintln!("\n{err}");
+            process::exit(1);
+        }
+    };
 
-    drop(tx);
+    let total_pixel = image_height * image_width;
+    let renderer = Renderer::new(&camera, &world, &lights).with_filter(filter);
+
+    let samples = match progressive {
+        Some(pass_samples) => {
+            let (film, first_pass) = match &resume {
+                Some(path) => {
+                    let checkpoint = Checkpoint::load(path)
+                        .and_then(|checkpoint| {
+                            checkpoint.validate(path, &settings)?;
+                            Ok(checkpoint)
+                        })
+                        .unwrap_or_else(|err| {
+                            eprintln!("{err}");
+                            process::exit(1);
+                        });
+                    let passes_completed = checkpoint.passes_completed();
+                    let film = checkpoint.into_film(filter);
+                    eprintln!(
+                        "Resuming after {} passes with {} spp",
+                        passes_completed,
+                        film.min_samples()
+                    );
+                    (film, passes_completed)
+                }
+                None => (renderer.film(), 0),
+            };
+            let cancellation = CancellationToken::new();
+            let handler_token = cancellation.clone();
+            ctrlc::set_handler(move || handler_token.cancel())
+                .expect("cannot install the Ctrl-C handler");
+            let renderer = renderer.with_cancellation(cancellation);
+            let film = render_progressive(
+                &renderer,
+                film,
+                first_pass,
+                samples_per_pixel,
+                pass_samples.max(1),
+                |film, passes_completed, last| {
+                    save(&film.resolve());
+                    let Some(path) = &checkpoint else {
+                        return;
+                    };
+                    if last || passes_completed % checkpoint_interval.max(1) == 0 {
+                        let result =
+                            Checkpoint::new(settings.clone(), passes_completed, film).save(path);
+                        if let Err(err) = result {
+                            eprintln!("{err}");
+                            process::exit(1);
+                        }
+                    }
+                },
+            );
+            film.samples().to_vec()
+        }
+        None => {
+            let framebuffer = renderer
+                .with_progress(|progress| {
+                    eprint!(
+                        "\rProcessed: {}/{} pixels. Elapsed: {:.1}s, ETA: {:.1}s             ",
+                        progress.pixels_done,
+                        progress.total_pixels,
+                        progress.elapsed.as_secs_f64(),
+                        progress.eta.as_secs_f64()
+                    );
+                })
+                .render();
+            save(&framebuffer.pixels);
+            framebuffer.samples
+        }
+    };
 
-    handle.join().expect("cannot join");
+    if adaptive {
+        let total_samples: u64 = samples.iter().map(|&n| n as u64).sum();
+        eprintln!(
+            "\nAdaptive sampling: {:.1} samples per pixel on average",
+            total_samples as f64 / total_pixel as f64
+        );
+    }
+    if let Some(path) = sample_heatmap {
+        let result = output::save_heatmap(&path, image_width, image_height, &samples, max_samples);
+        if let Err(err) = result {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    }
 
+    eprintln!(
+        "\nRender duration: {}s",
+        render_start.elapsed().as_secs_f64()
+    );
     let end = Instant::now();
     let duration = end.duration_since(start);
-    eprintln!("\nExecution duration: {}s", duration.as_secs_f64());
+    eprintln!("Execution duration: {}s", duration.as_secs_f64());
+}
+
+/// Renders the passes of `pass_samples` after the first `first_pass` until every pixel of
+/// `film` has `total_samples`, calling `snapshot` with the film, the number of passes completed
+/// and whether it is the last call. A cancelled pass does not count as completed.
+/// Cancelling the renderer stops the current pass early.
+fn render_progressive<T: Hittable + Sync>(
+    renderer: &Renderer<T>,
+    mut film: Film,
+    first_pass: u32,
+    total_samples: u32,
+    pass_samples: u32,
+    mut snapshot: impl FnMut(&Film, u32, bool),
+) -> Film {
+    let passes = total_samples
+        .div_ceil(pass_samples)
+        .saturating_sub(first_pass);
+    if passes == 0 {
+        snapshot(&film, first_pass, true);
+        return film;
+    }
+    for pass in 0..passes {
+        let passes_completed = first_pass + pass;
+        let target_samples = ((passes_completed + 1) * pass_samples).min(total_samples);
+        let pass_start = Instant::now();
+        if !renderer.render_pass(&mut film, target_samples) {
+            snapshot(&film, passes_completed, true);
+            eprintln!("Interrupted, wrote the image after {passes_completed} passes");
+            break;
+        }
+
+        snapshot(&film, passes_completed + 1, pass + 1 == passes);
+        eprintln!(
+            "Pass {}/{} ({} spp) done in {}s",
+            pass + 1,
+            passes,
+            film.min_samples(),
+            pass_start.elapsed().as_secs_f64()
+        );
+    }
+    film
 }
@@ -1,11 +1,10 @@
 use crate::{
     object::HitRecord,
-    ray::Ray,
-    utils::rand_double,
+    sampler::Sampler,
     vec3::{Color, Vec3},
 };
 
-use super::{Material, MaterialInteractResult};
+use super::{Material, ScatterSample};
 
 pub struct Dielectric {
     refraction_index: f64,
@@ -23,32 +22,35 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn interact(&self, r_in: &Ray, hit_record: &HitRecord) -> MaterialInteractResult {
-        let HitRecord { point, normal, .. } = hit_record;
+    fn sample(
+        &self,
+        hit_record: &HitRecord,
+        wo: &Vec3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterSample> {
+        let normal = &hit_record.normal;
         let ri = if hit_record.front_face {
             1.0 / self.refraction_index
         } else {
             self.refraction_index
         };
 
-        let unit_dir = r_in.dir.to_unit();
-        let cos_theta = f64::min(Vec3::dot(&-unit_dir, normal), 1.0);
+        let unit_dir = -wo;
+        let cos_theta = f64::min(Vec3::dot(wo, normal), 1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 
-        let dir = if ri * sin_theta > 1.0 || Dielectric::reflectance(cos_theta, ri) > rand_double()
-        {
-            unit_dir.reflect(normal)
-        } else {
-            unit_dir.refract(normal, ri)
-        };
+        let dir =
+            if ri * sin_theta > 1.0 || Dielectric::reflectance(cos_theta, ri) > sampler.get_1d() {
+                unit_dir.reflect(normal)
+            } else {
+                unit_dir.refract(normal, ri)
+            };
 
-        MaterialInteractResult::Scatter {
-            attenuation: Color::WHITE,
-            ray: Ray {
-                origin: *point,
-                time: r_in.time,
-                dir,
-            },
-        }
+        Some(ScatterSample {
+            direction: dir,
+            weight: Color::WHITE,
+            pdf: 0.0,
+            is_specular: true,
+        })
     }
 }
@@ -0,0 +1,56 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{
+    object::HitRecord,
+    sampler::{sample_uniform_sphere, Sampler},
+    texture::{SolidColorTexture, Texture},
+    vec3::{Color, Vec3},
+};
+
+use super::{Material, ScatterSample};
+
+const UNIFORM_SPHERE_PDF: f64 = 1.0 / (4.0 * PI);
+
+/// Phase function scattering equally in every direction, used inside participating media.
+pub struct Isotropic {
+    texture: Arc<dyn Texture + Sync + Send>,
+}
+
+impl Isotropic {
+    pub fn new_solid_color(albedo: Color) -> Self {
+        Self::new(Arc::new(SolidColorTexture::new(albedo)))
+    }
+
+    pub fn new(texture: Arc<dyn Texture + Sync + Send>) -> Self {
+        Self { texture }
+    }
+
+    fn albedo(&self, hit_record: &HitRecord) -> Color {
+        self.texture
+            .value(hit_record.u, hit_record.v, &hit_record.point)
+    }
+}
+
+impl Material for Isotropic {
+    fn sample(
+        &self,
+        hit_record: &HitRecord,
+        _wo: &Vec3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterSample> {
+        Some(ScatterSample {
+            direction: sample_uniform_sphere(sampler.get_2d()),
+            weight: self.albedo(hit_record),
+            pdf: UNIFORM_SPHERE_PDF,
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, hit_record: &HitRecord, _wi: &Vec3, _wo: &Vec3) -> Color {
+        self.albedo(hit_record) * UNIFORM_SPHERE_PDF
+    }
+
+    fn pdf(&self, _hit_record: &HitRecord, _wi: &Vec3, _wo: &Vec3) -> f64 {
+        UNIFORM_SPHERE_PDF
+    }
+}
@@ -1,13 +1,13 @@
-use std::sync::Arc;
+use std::{f64::consts::PI, sync::Arc};
 
 use crate::{
     object::HitRecord,
-    ray::Ray,
+    sampler::{sample_cosine_hemisphere, Sampler},
     texture::{SolidColorTexture, Texture},
-    vec3::{Color, Vec3},
+    vec3::{Color, Onb, Vec3},
 };
 
-use super::{Material, MaterialInteractResult};
+use super::{Material, ScatterSample};
 
 #[derive(Clone)]
 pub struct Lambertian {
@@ -23,27 +23,36 @@ impl Lambertian {
     pub fn new(texture: Arc<dyn Texture + Sync + Send>) -> Self {
         Self { texture }
     }
+
+    fn albedo(&self, hit_record: &HitRecord) -> Color {
+        self.texture
+            .value(hit_record.u, hit_record.v, &hit_record.point)
+    }
 }
 
 impl Material for Lambertian {
-    fn interact(&self, r_in: &Ray, hit_record: &HitRecord) -> MaterialInteractResult {
-        let _ = r_in;
-        let mut scatter_dir = hit_record.normal + Vec3::rand_unit();
-        if scatter_dir.is_near_zero() {
-            scatter_dir = hit_record.normal
-        }
+    fn sample(
+        &self,
+        hit_record: &HitRecord,
+        _wo: &Vec3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterSample> {
+        let local = sample_cosine_hemisphere(sampler.get_2d());
+        let direction = Onb::new(&hit_record.normal).transform(&local).to_unit();
 
-        let scattered = Ray {
-            origin: hit_record.point,
-            time: r_in.time,
-            dir: scatter_dir,
-        };
-        let attenuation = self
-            .texture
-            .value(hit_record.u, hit_record.v, &hit_record.point);
-        MaterialInteractResult::Scatter {
-            attenuation,
-            ray: scattered,
-        }
+        Some(ScatterSample {
+            direction,
+            weight: self.albedo(hit_record),
+            pdf: f64::max(hit_record.normal.dot(&direction), 0.0) / PI,
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wi: &Vec3, wo: &Vec3) -> Color {
+        self.albedo(hit_record) * self.pdf(hit_record, wi, wo)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wi: &Vec3, _wo: &Vec3) -> f64 {
+        f64::max(hit_record.normal.dot(wi), 0.0) / PI
     }
 }
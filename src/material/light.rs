@@ -2,12 +2,12 @@ use std::sync::Arc;
 
 use crate::{
     object::HitRecord,
-    ray::Ray,
+    sampler::Sampler,
     texture::{SolidColorTexture, Texture},
-    vec3::Color,
+    vec3::{Color, Vec3},
 };
 
-use super::{Material, MaterialInteractResult};
+use super::{Material, ScatterSample};
 
 pub struct DiffuseLight {
     texture: Arc<dyn Texture + Send + Sync>,
@@ -24,10 +24,21 @@ impl DiffuseLight {
 }
 
 impl Material for DiffuseLight {
-    fn interact(&self, _r_in: &Ray, hit_record: &HitRecord) -> MaterialInteractResult {
-        let color = self
-            .texture
-            .value(hit_record.u, hit_record.v, &hit_record.point);
-        MaterialInteractResult::Emitted { color }
+    fn sample(
+        &self,
+        _hit_record: &HitRecord,
+        _wo: &Vec3,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<ScatterSample> {
+        None
+    }
+
+    fn emitted(&self, hit_record: &HitRecord) -> Color {
+        self.texture
+            .value(hit_record.u, hit_record.v, &hit_record.point)
+    }
+
+    fn is_emissive(&self) -> bool {
+        true
     }
 }
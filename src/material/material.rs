@@ -1,4 +1,9 @@
-use crate::{object::HitRecord, ray::Ray, vec3::Color};
+use crate::{
+    object::HitRecord,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Color, Vec3},
+};
 
 pub enum MaterialInteractResult {
     Scatter { attenuation: Color, ray: Ray },
@@ -6,6 +11,69 @@ pub enum MaterialInteractResult {
     None,
 }
 
+/// A direction drawn from a material's scattering distribution.
+pub struct ScatterSample {
+    /// Unit direction the incoming light is scattered towards (`wi`).
+    pub direction: Vec3,
+    /// `eval / pdf` for the sampled direction, or the reflectance of a specular lobe.
+    pub weight: Color,
+    /// Solid angle density of `direction`. Only meaningful for non-specular samples.
+    pub pdf: f64,
+    /// Whether the sample comes from a delta distribution, which `eval` and `pdf` cannot represent.
+    pub is_specular: bool,
+}
+
+/// Directions follow the usual convention: `wo` points from the hit point towards the viewer and
+/// `wi` towards where the light comes from, both unit length.
 pub trait Material {
-    fn interact(&self, r_in: &Ray, hit_record: &HitRecord) -> MaterialInteractResult;
+    /// Draws an incoming direction for light leaving towards `wo`, or `None` if the ray is absorbed.
+    fn sample(
+        &self,
+        hit_record: &HitRecord,
+        wo: &Vec3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterSample>;
+
+    /// Scattering function for the pair of directions, including the cosine with the normal for
+    /// surfaces, so that phase functions in media fit the same interface.
+    fn eval(&self, _hit_record: &HitRecord, _wi: &Vec3, _wo: &Vec3) -> Color {
+        Color::BLACK
+    }
+
+    /// Solid angle density with which `sample` returns `wi` for the given `wo`.
+    fn pdf(&self, _hit_record: &HitRecord, _wi: &Vec3, _wo: &Vec3) -> f64 {
+        0.0
+    }
+
+    fn emitted(&self, _hit_record: &HitRecord) -> Color {
+        Color::BLACK
+    }
+
+    fn is_emissive(&self) -> bool {
+        false
+    }
+
+    fn interact(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+        sampler: &mut dyn Sampler,
+    ) -> MaterialInteractResult {
+        if self.is_emissive() {
+            return MaterialInteractResult::Emitted {
+                color: self.emitted(hit_record),
+            };
+        }
+        match self.sample(hit_record, &-r_in.dir.to_unit(), sampler) {
+            Some(sample) => MaterialInteractResult::Scatter {
+                attenuation: sample.weight,
+                ray: Ray {
+                    origin: hit_record.point,
+                    dir: sample.direction,
+                    time: r_in.time,
+                },
+            },
+            None => MaterialInteractResult::None,
+        }
+    }
 }
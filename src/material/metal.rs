@@ -1,10 +1,12 @@
+use std::f64::consts::PI;
+
 use crate::{
     object::HitRecord,
-    ray::Ray,
+    sampler::{sample_uniform_sphere, Sampler},
     vec3::{Color, Vec3},
 };
 
-use super::{Material, MaterialInteractResult};
+use super::{Material, ScatterSample};
 
 pub struct Metal {
     albedo: Color,
@@ -18,25 +20,102 @@ impl Metal {
             fuzz: fuzz.clamp(0.0, 1.0),
         }
     }
+
+    /// Density of `wi` for the fuzzed lobe around the unit mirror direction `reflected`.
+    ///
+    /// `sample` aims at a uniform point on the sphere of radius `fuzz` around `reflected`. A
+    /// ray along `wi` crosses that sphere at distances `t` with `t^2 - 2ct + 1 - fuzz^2 = 0`,
+    /// `c = wi . reflected`, and converting the sphere's area density to solid angle at both
+    /// crossings gives `(c^2 + d) / (2 pi fuzz sqrt(d))`, where `d` is the discriminant.
+    fn fuzz_pdf(&self, reflected: &Vec3, wi: &Vec3) -> f64 {
+        let c = wi.dot(reflected);
+        let discriminant = c * c - 1.0 + self.fuzz * self.fuzz;
+        if c <= 0.0 || discriminant <= 0.0 {
+            return 0.0;
+        }
+        (c * c + discriminant) / (2.0 * PI * self.fuzz * discriminant.sqrt())
+    }
 }
 
 impl Material for Metal {
-    fn interact(&self, r_in: &Ray, hit_record: &HitRecord) -> MaterialInteractResult {
-        let mut reflected_dir = r_in.dir.reflect(&hit_record.normal);
-        if self.fuzz > 0.0 {
-            reflected_dir = reflected_dir.to_unit() + self.fuzz * Vec3::rand_unit();
+    /// A perfect mirror when `fuzz` is zero; otherwise directions below the surface are
+    /// absorbed, so `eval / pdf` is the albedo wherever the lobe reaches.
+    fn sample(
+        &self,
+        hit_record: &HitRecord,
+        wo: &Vec3,
+        sampler: &mut dyn Sampler,
+    ) -> Option<ScatterSample> {
+        let reflected = (-wo).reflect(&hit_record.normal).to_unit();
+        if self.fuzz == 0.0 {
+            return (reflected.dot(&hit_record.normal) > 0.0).then_some(ScatterSample {
+                direction: reflected,
+                weight: self.albedo,
+                pdf: 0.0,
+                is_specular: true,
+            });
         }
-        if reflected_dir.dot(&hit_record.normal) <= 0.0 {
-            return MaterialInteractResult::None;
+
+        let direction = (reflected + self.fuzz * sample_uniform_sphere(sampler.get_2d())).to_unit();
+        if direction.dot(&hit_record.normal) <= 0.0 {
+            return None;
+        }
+        Some(ScatterSample {
+            direction,
+            weight: self.albedo,
+            pdf: self.fuzz_pdf(&reflected, &direction),
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, hit_record: &HitRecord, wi: &Vec3, wo: &Vec3) -> Color {
+        self.albedo * self.pdf(hit_record, wi, wo)
+    }
+
+    fn pdf(&self, hit_record: &HitRecord, wi: &Vec3, wo: &Vec3) -> f64 {
+        if self.fuzz == 0.0 || wi.dot(&hit_record.normal) <= 0.0 {
+            return 0.0;
         }
-        let scattered_ray = Ray {
-            dir: reflected_dir,
-            origin: hit_record.point,
-            time: r_in.time,
-        };
-        MaterialInteractResult::Scatter {
-            attenuation: self.albedo,
-            ray: scattered_ray,
+        let reflected = (-wo).reflect(&hit_record.normal).to_unit();
+        self.fuzz_pdf(&reflected, wi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::{
+        sampler::sample_uniform_sphere,
+        vec3::{Color, Vec3},
+    };
+
+    use super::Metal;
+
+    #[test]
+    fn fuzz_density_matches_the_sampled_directions() {
+        for fuzz in [0.1, 0.5, 1.0] {
+            let metal = Metal::new(Color::all(0.8), fuzz);
+            let reflected = Vec3::new(0.3, 0.9, -0.2).to_unit();
+            // the mean of 1 / pdf over sampled directions is the solid angle they cover
+            const STEPS: u32 = 300;
+            let mut total = 0.0;
+            for i in 0..STEPS {
+                for j in 0..STEPS {
+                    let u = (
+                        (i as f64 + 0.5) / STEPS as f64,
+                        (j as f64 + 0.5) / STEPS as f64,
+                    );
+                    let direction = (reflected + fuzz * sample_uniform_sphere(u)).to_unit();
+                    total += 1.0 / metal.fuzz_pdf(&reflected, &direction);
+                }
+            }
+            let covered = total / (STEPS * STEPS) as f64;
+            let cone = 2.0 * PI * (1.0 - (1.0 - fuzz * fuzz).sqrt());
+            assert!(
+                (covered / cone - 1.0).abs() < 0.01,
+                "{fuzz}: {covered} vs {cone}"
+            );
         }
     }
 }
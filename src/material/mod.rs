@@ -1,12 +1,15 @@
+#[allow(clippy::module_inception)]
 mod material;
 
 mod dielectric;
+mod isotropic;
 mod lambertian;
 mod light;
 mod metal;
 
 pub use dielectric::Dielectric;
+pub use isotropic::Isotropic;
 pub use lambertian::Lambertian;
 pub use light::DiffuseLight;
-pub use material::{Material, MaterialInteractResult};
+pub use material::{Material, MaterialInteractResult, ScatterSample};
 pub use metal::Metal;
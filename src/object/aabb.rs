@@ -62,6 +62,37 @@ impl Aabb {
         }
     }
 
+    pub fn surface_area(&self) -> f64 {
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    pub fn centroid(&self) -> Point {
+        Point::new(
+            (self.x.min + self.x.max) / 2.0,
+            (self.y.min + self.y.max) / 2.0,
+            (self.z.min + self.z.max) / 2.0,
+        )
+    }
+
+    /// Slab test against a precomputed inverse ray direction, restricted to `interval`.
+    pub fn hit_inverse(&self, origin: &Point, inv_dir: &Vec3, interval: &Interval) -> bool {
+        let (mut ray_min, mut ray_max) = (interval.min, interval.max);
+        for axis_index in 0..3 {
+            let ax = self[axis_index];
+            let t0 = (ax.min - origin[axis_index]) * inv_dir[axis_index];
+            let t1 = (ax.max - origin[axis_index]) * inv_dir[axis_index];
+
+            ray_min = f64::max(ray_min, f64::min(t0, t1));
+            ray_max = f64::min(ray_max, f64::max(t0, t1));
+
+            if ray_max < ray_min {
+                return false;
+            }
+        }
+        true
+    }
+
     pub fn hit(&self, r: &Ray) -> Option<Interval> {
         let Ray { dir, origin, .. } = r;
         let (mut ray_min, mut ray_max) = (f64::NEG_INFINITY, f64::INFINITY);
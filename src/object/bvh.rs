@@ -1,89 +1,362 @@
 use std::{cmp::Ordering, sync::Arc};
 
-use crate::{interval::Interval, ray::Ray};
+use crate::{
+    interval::Interval,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Point, Vec3},
+};
 
-use super::{aabb::Aabb, hittable_list::HittableVec, Hittable};
+use super::{aabb::Aabb, hittable_list::HittableVec, HitRecord, Hittable};
 
-// Implementation of the Bounding Volume Hierarchy system
-pub struct BVHTree {
-    left: Arc<dyn Hittable + Send + Sync>,
-    right: Arc<dyn Hittable + Send + Sync>,
-    bbox: Aabb,
+/// How a BVH node partitions its primitives between its two children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitMethod {
+    /// Split at the median of the primitives' lower bounds along the longest axis.
+    ///
+    /// With `max_leaf_size` 1 this builds the same tree as the original BVH, which
+    /// sorted every node fully; larger sizes stop earlier at multi-primitive leaves.
+    Median,
+    /// Pick the cheapest of a set of binned candidate planes by the surface area heuristic.
+    Sah,
 }
 
-impl BVHTree {
-    fn new(left: Arc<dyn Hittable + Send + Sync>, right: Arc<dyn Hittable + Send + Sync>) -> Self {
-        Self {
-            bbox: Aabb::join(&left.bounding_box(), &right.bounding_box()),
-            left,
-            right,
-        }
-    }
+#[derive(Clone, Copy, Debug)]
+pub struct BVHOptions {
+    pub split: SplitMethod,
+    /// Nodes with at most this many primitives may become leaves.
+    pub max_leaf_size: usize,
+}
 
-    fn leaf(hittable: Arc<dyn Hittable + Send + Sync>) -> Self {
+impl Default for BVHOptions {
+    fn default() -> Self {
         Self {
-            bbox: hittable.bounding_box().clone(),
-            left: hittable.clone(),
-            right: hittable.clone(),
+            split: SplitMethod::Sah,
+            max_leaf_size: 4,
         }
     }
+}
+
+const SAH_BINS: usize = 12;
+// cost of visiting a node, relative to intersecting one primitive
+const TRAVERSAL_COST: f64 = 0.125;
+const MAX_TRAVERSAL_DEPTH: usize = 64;
+
+struct Primitive {
+    object: Arc<dyn Hittable + Send + Sync>,
+    bbox: Aabb,
+    centroid: Point,
+}
+
+#[derive(Clone, Copy)]
+enum LinearNodeKind {
+    /// Primitives `first..first + count` of the tree's primitive list.
+    Leaf { first: u32, count: u32 },
+    /// The first child directly follows its parent; the second one is at `second_child`.
+    Interior { second_child: u32, axis: u8 },
+}
+
+#[derive(Clone, Copy)]
+struct LinearNode {
+    bbox: Aabb,
+    kind: LinearNodeKind,
+}
+
+// Implementation of the Bounding Volume Hierarchy system, stored as a depth-first array of nodes
+pub struct BVHTree {
+    nodes: Vec<LinearNode>,
+    primitives: HittableVec,
+}
 
+impl BVHTree {
     pub fn from_list(objects: &HittableVec) -> Self {
-        let length = objects.len();
-        return match length {
-            1 => Self::leaf(objects[0].clone()),
-            2 => Self::new(objects[0].clone(), objects[1].clone()),
-            _ => {
-                let mut bbox = Aabb::EMPTY;
-                for obj in objects {
-                    bbox = Aabb::join(obj.bounding_box(), &bbox)
+        Self::build(objects, BVHOptions::default())
+    }
+
+    /// Builds a tree over `objects`. Nested lists of objects get bottom-level trees of their
+    /// own (see `Hittable::accelerated`) instead of being scanned linearly from a leaf.
+    pub fn build(objects: &HittableVec, options: BVHOptions) -> Self {
+        let mut primitives: Vec<Primitive> = objects
+            .iter()
+            .map(|object| {
+                let object = object
+                    .accelerated(&options)
+                    .unwrap_or_else(|| object.clone());
+                let bbox = *object.bounding_box();
+                Primitive {
+                    object,
+                    centroid: bbox.centroid(),
+                    bbox,
                 }
-                let axis = bbox.longest_axis() as usize;
+            })
+            .collect();
 
-                let mut sortable_objects = objects.clone();
-                sortable_objects.sort_by(|a, b| {
-                    let a_interval = a.bounding_box()[axis];
-                    let b_interval = b.bounding_box()[axis];
+        let mut tree = Self {
+            nodes: Vec::with_capacity(2 * primitives.len()),
+            primitives: Vec::with_capacity(primitives.len()),
+        };
+        tree.build_node(&mut primitives, &options, 0);
+        tree
+    }
 
-                    if a_interval.min < b_interval.min {
-                        return Ordering::Less;
-                    }
-                    if a_interval.min > b_interval.min {
-                        return Ordering::Greater;
-                    }
-                    return Ordering::Equal;
-                });
-                let mid = length / 2;
-                let left_tree = Arc::new(Self::from_list(&sortable_objects[0..mid].to_vec()));
-                let right_tree = Arc::new(Self::from_list(&sortable_objects[mid..].to_vec()));
-                Self {
-                    left: left_tree,
-                    right: right_tree,
-                    bbox,
+    fn push_leaf(&mut self, primitives: &[Primitive], bbox: Aabb) -> usize {
+        self.nodes.push(LinearNode {
+            bbox,
+            kind: LinearNodeKind::Leaf {
+                first: self.primitives.len() as u32,
+                count: primitives.len() as u32,
+            },
+        });
+        self.primitives
+            .extend(primitives.iter().map(|p| p.object.clone()));
+        self.nodes.len() - 1
+    }
+
+    /// Appends the subtree over `primitives` in depth-first order and returns the index of its root.
+    fn build_node(
+        &mut self,
+        primitives: &mut [Primitive],
+        options: &BVHOptions,
+        depth: usize,
+    ) -> usize {
+        let bbox = primitives
+            .iter()
+            .fold(Aabb::EMPTY, |bbox, p| Aabb::join(&bbox, &p.bbox));
+        let length = primitives.len();
+        if length <= 1
+            || depth + 1 >= MAX_TRAVERSAL_DEPTH
+            || (options.split == SplitMethod::Median && length <= options.max_leaf_size)
+        {
+            return self.push_leaf(primitives, bbox);
+        }
+
+        let (mid, axis) = match options.split {
+            SplitMethod::Median => Self::median_split(primitives, &bbox),
+            SplitMethod::Sah => match Self::sah_split(primitives, &bbox, options) {
+                Some(split) => split,
+                None if length <= options.max_leaf_size => return self.push_leaf(primitives, bbox),
+                // every candidate plane is degenerate, fall back to splitting the list in half
+                None => Self::median_split(primitives, &bbox),
+            },
+        };
+
+        let index = self.nodes.len();
+        self.nodes.push(LinearNode {
+            bbox,
+            kind: LinearNodeKind::Interior {
+                second_child: 0,
+                axis: axis as u8,
+            },
+        });
+
+        let (left, right) = primitives.split_at_mut(mid);
+        self.build_node(left, options, depth + 1);
+        let second = self.build_node(right, options, depth + 1);
+        self.nodes[index].kind = LinearNodeKind::Interior {
+            second_child: second as u32,
+            axis: axis as u8,
+        };
+        index
+    }
+
+    fn median_split(primitives: &mut [Primitive], bbox: &Aabb) -> (usize, usize) {
+        let axis = bbox.longest_axis() as usize;
+        let mid = primitives.len() / 2;
+        primitives.select_nth_unstable_by(mid, |a, b| {
+            a.bbox[axis]
+                .min
+                .partial_cmp(&b.bbox[axis].min)
+                .unwrap_or(Ordering::Equal)
+        });
+        (mid, axis)
+    }
+
+    /// Evaluates `SAH_BINS - 1` candidate planes per axis over the centroid bounds and partitions
+    /// the primitives at the cheapest one, returning the partition point and axis. Returns `None`
+    /// when making a leaf is cheaper or no plane separates the centroids.
+    fn sah_split(
+        primitives: &mut [Primitive],
+        bbox: &Aabb,
+        options: &BVHOptions,
+    ) -> Option<(usize, usize)> {
+        let mut centroid_bounds = [Interval::EMPTY; 3];
+        for p in primitives.iter() {
+            for (axis, bounds) in centroid_bounds.iter_mut().enumerate() {
+                *bounds =
+                    Interval::join(bounds, &Interval::new(p.centroid[axis], p.centroid[axis]));
+            }
+        }
+        let parent_area = bbox.surface_area();
+
+        let mut best: Option<(f64, usize, usize)> = None;
+        for (axis, extent) in centroid_bounds.iter().enumerate() {
+            if extent.size() <= 0.0 {
+                continue;
+            }
+
+            let mut counts = [0usize; SAH_BINS];
+            let mut bounds = [Aabb::EMPTY; SAH_BINS];
+            for p in primitives.iter() {
+                let bin = Self::sah_bin(&p.centroid, axis, extent);
+                counts[bin] += 1;
+                bounds[bin] = Aabb::join(&bounds[bin], &p.bbox);
+            }
+
+            // sweep from the right to get the cost of every suffix of bins
+            let mut right_area = [0.0; SAH_BINS];
+            let mut right_count = [0usize; SAH_BINS];
+            let (mut acc_box, mut acc_count) = (Aabb::EMPTY, 0);
+            for bin in (1..SAH_BINS).rev() {
+                acc_box = Aabb::join(&acc_box, &bounds[bin]);
+                acc_count += counts[bin];
+                right_area[bin] = acc_box.surface_area();
+                right_count[bin] = acc_count;
+            }
+
+            let (mut acc_box, mut acc_count) = (Aabb::EMPTY, 0);
+            for split in 1..SAH_BINS {
+                acc_box = Aabb::join(&acc_box, &bounds[split - 1]);
+                acc_count += counts[split - 1];
+                if acc_count == 0 || right_count[split] == 0 {
+                    continue;
+                }
+                let cost = TRAVERSAL_COST
+                    + (acc_box.surface_area() * acc_count as f64
+                        + right_area[split] * right_count[split] as f64)
+                        / parent_area;
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, split));
                 }
             }
-        };
+        }
+
+        let (cost, axis, split) = best?;
+        if primitives.len() <= options.max_leaf_size && cost >= primitives.len() as f64 {
+            return None;
+        }
+
+        let extent = centroid_bounds[axis];
+        let mut mid = 0;
+        for i in 0..primitives.len() {
+            if Self::sah_bin(&primitives[i].centroid, axis, &extent) < split {
+                primitives.swap(i, mid);
+                mid += 1;
+            }
+        }
+        Some((mid, axis))
+    }
+
+    fn sah_bin(centroid: &Point, axis: usize, extent: &Interval) -> usize {
+        let offset = (centroid[axis] - extent.min) / extent.size();
+        ((offset * SAH_BINS as f64) as usize).min(SAH_BINS - 1)
     }
 }
 
 impl Hittable for BVHTree {
-    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<super::HitRecord> {
-        if self.bbox.hit(ray).is_none() {
-            return None;
-        }
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        let inv_dir = Vec3::new(1.0 / ray.dir.x, 1.0 / ray.dir.y, 1.0 / ray.dir.z);
+        let dir_is_negative = [inv_dir.x < 0.0, inv_dir.y < 0.0, inv_dir.z < 0.0];
+
+        let mut closest_hit_record: Option<HitRecord> = None;
+        let mut closest_so_far = interval.max;
+
+        let mut stack = [0u32; MAX_TRAVERSAL_DEPTH];
+        let mut stack_size = 0;
+        let mut current = 0;
 
-        let left_hit = self.left.hit(ray, interval);
+        loop {
+            let node = &self.nodes[current];
+            let range = Interval::new(interval.min, closest_so_far);
+
+            if node.bbox.hit_inverse(&ray.origin, &inv_dir, &range) {
+                match node.kind {
+                    LinearNodeKind::Leaf { first, count } => {
+                        let first = first as usize;
+                        for obj in &self.primitives[first..first + count as usize] {
+                            if let Some(hit) =
+                                obj.hit(ray, &Interval::new(interval.min, closest_so_far), sampler)
+                            {
+                                closest_so_far = hit.t;
+                                closest_hit_record = Some(hit);
+                            }
+                        }
+                    }
+                    LinearNodeKind::Interior { second_child, axis } => {
+                        // visit the child on the near side of the split first
+                        let first_child = current as u32 + 1;
+                        let (near, far) = if dir_is_negative[axis as usize] {
+                            (second_child, first_child)
+                        } else {
+                            (first_child, second_child)
+                        };
+                        stack[stack_size] = far;
+                        stack_size += 1;
+                        current = near as usize;
+                        continue;
+                    }
+                }
+            }
 
-        if let Some(left_hit_record) = left_hit {
-            let right_interval = Interval::new(interval.min, left_hit_record.t);
-            let right_hit = self.right.hit(ray, &right_interval);
-            return right_hit.or(Some(left_hit_record));
-        } else {
-            return self.right.hit(ray, interval);
+            if stack_size == 0 {
+                break;
+            }
+            stack_size -= 1;
+            current = stack[stack_size] as usize;
         }
+
+        closest_hit_record
     }
 
     fn bounding_box(&self) -> &Aabb {
-        &self.bbox
+        &self.nodes[0].bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::Lambertian,
+        object::{HittableList, Sphere},
+        sampler::RandomSampler,
+        utils::rand_range,
+    };
+
+    #[test]
+    fn test_bvh_matches_linear_scan() {
+        let material = Arc::new(Lambertian::new_solid_color(Vec3::all(0.5)));
+        let mut list = HittableList::empty();
+        for _ in 0..200 {
+            let center = Vec3::rand_range(-10.0..10.0);
+            list.add(Sphere::stationary(
+                center,
+                rand_range(0.1..1.0),
+                material.clone(),
+            ));
+        }
+
+        for split in [SplitMethod::Median, SplitMethod::Sah] {
+            let tree = BVHTree::build(
+                list.objects(),
+                BVHOptions {
+                    split,
+                    max_leaf_size: 4,
+                },
+            );
+            for _ in 0..500 {
+                let ray = Ray {
+                    origin: Vec3::rand_range(-15.0..15.0),
+                    dir: Vec3::rand_unit(),
+                    time: 0.0,
+                };
+                let expected = list
+                    .hit(&ray, &Interval::POSITIVE, &mut RandomSampler)
+                    .map(|hit| hit.t);
+                let actual = tree
+                    .hit(&ray, &Interval::POSITIVE, &mut RandomSampler)
+                    .map(|hit| hit.t);
+                assert_eq!(expected, actual);
+            }
+        }
     }
 }
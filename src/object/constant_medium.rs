@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::{
+    interval::Interval,
+    material::{Isotropic, Material},
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Color, Vec3},
+};
+
+use super::{Aabb, BVHOptions, HitRecord, Hittable};
+
+/// Gap used to find the exit point of the boundary after its entry point.
+const BOUNDARY_EPSILON: f64 = 0.0001;
+
+/// A volume of uniform density filling a closed, convex boundary, such as smoke or fog.
+pub struct ConstantMedium<H: Hittable + Send + Sync> {
+    boundary: H,
+    neg_inv_density: f64,
+    phase_function: Arc<dyn Material + Send + Sync>,
+}
+
+impl<H: Hittable + Send + Sync> ConstantMedium<H> {
+    /// Panics if `density` is not positive.
+    pub fn new(boundary: H, density: f64, albedo: Color) -> Self {
+        assert!(
+            density > 0.0,
+            "medium density must be positive, got {density}"
+        );
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function: Arc::new(Isotropic::new_solid_color(albedo)),
+        }
+    }
+}
+
+impl<H: Hittable + Send + Sync> Hittable for ConstantMedium<H> {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        // search the whole line so that a ray starting inside still finds the entry point behind it
+        let entry = self.boundary.hit(ray, &Interval::UNIVERSE, sampler)?;
+        let exit = self.boundary.hit(
+            ray,
+            &Interval::new(entry.t + BOUNDARY_EPSILON, f64::INFINITY),
+            sampler,
+        )?;
+
+        let t_enter = f64::max(entry.t, f64::max(interval.min, 0.0));
+        let t_exit = f64::min(exit.t, interval.max);
+        if t_enter >= t_exit {
+            return None;
+        }
+
+        let ray_length = ray.dir.length();
+        let distance_inside = (t_exit - t_enter) * ray_length;
+        let hit_distance = self.neg_inv_density * sampler.get_1d().ln();
+        if hit_distance > distance_inside {
+            return None;
+        }
+
+        let t = t_enter + hit_distance / ray_length;
+        Some(HitRecord {
+            point: ray.at(t),
+            // the normal and side are meaningless inside a volume
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            t,
+            front_face: true,
+            u: 0.0,
+            v: 0.0,
+            material: self.phase_function.clone(),
+        })
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        self.boundary.bounding_box()
+    }
+
+    fn accelerated(&self, options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        Some(Arc::new(ConstantMedium {
+            boundary: self.boundary.accelerated(options)?,
+            neg_inv_density: self.neg_inv_density,
+            phase_function: self.phase_function.clone(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        interval::Interval,
+        material::Lambertian,
+        object::{Hittable, Sphere},
+        ray::Ray,
+        sampler::RandomSampler,
+        vec3::{Color, Point, Vec3},
+    };
+
+    use super::ConstantMedium;
+
+    fn fog(density: f64) -> ConstantMedium<Sphere> {
+        let boundary = Sphere::stationary(
+            Point::ZERO,
+            1.0,
+            Arc::new(Lambertian::new_solid_color(Color::WHITE)),
+        );
+        ConstantMedium::new(boundary, density, Color::WHITE)
+    }
+
+    #[test]
+    fn ray_starting_inside_scatters_within_the_boundary() {
+        let ray = Ray {
+            origin: Point::ZERO,
+            dir: Vec3::new(0.0, 0.0, 1.0),
+            time: 0.0,
+        };
+        let interval = Interval::new(0.001, f64::INFINITY);
+
+        let hit = fog(1e6)
+            .hit(&ray, &interval, &mut RandomSampler)
+            .expect("dense medium always scatters");
+        assert!(interval.surrounds(hit.t) && hit.t < 1.0);
+
+        assert!(fog(1e-9).hit(&ray, &interval, &mut RandomSampler).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "medium density must be positive")]
+    fn zero_density_is_rejected() {
+        fog(0.0);
+    }
+}
@@ -1,12 +1,12 @@
 use std::sync::Arc;
 
+use super::BVHOptions;
 use crate::interval::Interval;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::sampler::Sampler;
 use crate::vec3::{Point, Vec3};
 
-use super::Translate;
-
 #[derive(Clone)]
 pub struct HitRecord {
     pub point: Point,
@@ -49,7 +49,56 @@ impl HitRecord {
 }
 
 pub trait Hittable {
-    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord>;
+    /// Closest intersection of `ray` within `interval`. Surfaces ignore `sampler`; volumes
+    /// draw the distance at which the ray scatters from it.
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord>;
 
     fn bounding_box(&self) -> &super::Aabb;
+
+    /// Solid angle density of `random_direction` generating `direction` from `origin`.
+    fn pdf_value(&self, _origin: &Point, _direction: &Vec3) -> f64 {
+        0.0
+    }
+
+    /// Samples a direction from `origin` towards a point on this object.
+    fn random_direction(&self, _origin: &Point, _sampler: &mut dyn Sampler) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+
+    /// Whether this object emits light and supports direct sampling.
+    fn is_emitter(&self) -> bool {
+        false
+    }
+
+    /// A copy of this object in which every nested list of objects sits behind its own
+    /// bottom-level BVH, or `None` if there is no list to accelerate.
+    fn accelerated(&self, _options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        None
+    }
+}
+
+impl<T: Hittable + ?Sized> Hittable for Arc<T> {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        (**self).hit(ray, interval, sampler)
+    }
+
+    fn bounding_box(&self) -> &super::Aabb {
+        (**self).bounding_box()
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vec3) -> f64 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn random_direction(&self, origin: &Point, sampler: &mut dyn Sampler) -> Vec3 {
+        (**self).random_direction(origin, sampler)
+    }
+
+    fn is_emitter(&self) -> bool {
+        (**self).is_emitter()
+    }
+
+    fn accelerated(&self, options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        (**self).accelerated(options)
+    }
 }
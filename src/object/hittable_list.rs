@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
-use super::{Aabb, HitRecord, Hittable, Quad};
+use super::{Aabb, BVHOptions, BVHTree, HitRecord, Hittable, Quad};
 use crate::{
     interval::Interval,
     material::Material,
     ray::Ray,
+    sampler::Sampler,
     vec3::{Point, Vec3},
 };
 pub type HittableVec = Vec<Arc<dyn Hittable + Send + Sync>>;
@@ -26,13 +27,35 @@ impl HittableList {
         &self.objects
     }
 
+    /// Collects the objects that emit light, for direct light sampling. Lights nested in lists
+    /// or placed by `Translate`, `YRotate` or `Transform` are found; emitters inside BVHs,
+    /// meshes, instances, motion transforms and volumes, and moving spheres, are not, and are
+    /// only reached by material sampling.
+    pub fn lights(&self) -> Self {
+        let mut lights = Self::empty();
+        for obj in self.objects.iter().filter(|obj| obj.is_emitter()) {
+            lights.add_shared(obj.clone());
+        }
+        lights
+    }
+
+    fn emitters(&self) -> impl Iterator<Item = &Arc<dyn Hittable + Send + Sync>> {
+        self.objects.iter().filter(|obj| obj.is_emitter())
+    }
+
     pub fn add<T: Hittable + Send + Sync + 'static>(&mut self, obj: T) {
-        self.bbox = Aabb::join(&(obj.bounding_box()), &self.bbox);
+        self.bbox = Aabb::join(obj.bounding_box(), &self.bbox);
         self.objects.push(Arc::new(obj));
     }
 
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.bbox = Aabb::ZERO;
+    }
+
+    pub fn add_shared(&mut self, obj: Arc<dyn Hittable + Send + Sync>) {
+        self.bbox = Aabb::join(obj.bounding_box(), &self.bbox);
+        self.objects.push(obj);
     }
 
     pub fn rectangular_box(a: &Point, b: &Point, mat: Arc<dyn Material + Send + Sync>) -> Self {
@@ -86,12 +109,12 @@ impl HittableList {
 }
 
 impl Hittable for HittableList {
-    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, ray_t: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
         let mut closest_hit_record: Option<HitRecord> = None;
         let mut closest_so_far = ray_t.max;
 
         for obj in &self.objects {
-            if let Some(hit) = obj.hit(ray, &Interval::new(ray_t.min, closest_so_far)) {
+            if let Some(hit) = obj.hit(ray, &Interval::new(ray_t.min, closest_so_far), sampler) {
                 closest_so_far = hit.t;
                 closest_hit_record = Some(hit);
             };
@@ -102,4 +125,33 @@ impl Hittable for HittableList {
     fn bounding_box(&self) -> &Aabb {
         &self.bbox
     }
+
+    /// Density of `random_direction`, which picks one of the emitters uniformly.
+    fn pdf_value(&self, origin: &Point, direction: &Vec3) -> f64 {
+        let count = self.emitters().count();
+        if count == 0 {
+            return 0.0;
+        }
+        let weight = 1.0 / count as f64;
+        self.emitters()
+            .map(|obj| weight * obj.pdf_value(origin, direction))
+            .sum()
+    }
+
+    fn random_direction(&self, origin: &Point, sampler: &mut dyn Sampler) -> Vec3 {
+        let count = self.emitters().count();
+        let index = ((sampler.get_1d() * count as f64) as usize).min(count.saturating_sub(1));
+        match self.emitters().nth(index) {
+            Some(obj) => obj.random_direction(origin, sampler),
+            None => Vec3::new(1.0, 0.0, 0.0),
+        }
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.objects.iter().any(|obj| obj.is_emitter())
+    }
+
+    fn accelerated(&self, options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        Some(Arc::new(BVHTree::build(&self.objects, *options)))
+    }
 }
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use crate::{interval::Interval, material::Material, ray::Ray, sampler::Sampler, vec3::Matrix4};
+
+use super::{transform::Transform, Aabb, BVHTree, HitRecord, Hittable};
+
+/// A placement of shared prototype geometry. Every instance of a prototype refers to the same
+/// tree, so memory grows with the number of unique models rather than with the number of
+/// copies in the scene.
+pub struct Instance {
+    transformed: Transform<Arc<BVHTree>>,
+    /// Replaces the materials of the prototype's surfaces when set
+    material: Option<Arc<dyn Material + Send + Sync>>,
+}
+
+impl Instance {
+    /// Panics if `matrix` is singular.
+    pub fn new(prototype: Arc<BVHTree>, matrix: Matrix4) -> Self {
+        Self {
+            transformed: Transform::new(matrix, prototype),
+            material: None,
+        }
+    }
+
+    pub fn with_material(mut self, material: Arc<dyn Material + Send + Sync>) -> Self {
+        self.material = Some(material);
+        self
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        let mut hit = self.transformed.hit(ray, interval, sampler)?;
+        if let Some(material) = &self.material {
+            hit.material = material.clone();
+        }
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        self.transformed.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        interval::Interval,
+        material::{Lambertian, Material},
+        object::{BVHTree, Hittable, HittableList, Sphere},
+        ray::Ray,
+        sampler::RandomSampler,
+        vec3::{Color, Matrix4, Point, Vec3},
+    };
+
+    use super::Instance;
+
+    #[test]
+    fn instances_share_a_prototype_and_override_its_material() {
+        let grey: Arc<dyn Material + Send + Sync> =
+            Arc::new(Lambertian::new_solid_color(Color::all(0.5)));
+        let red: Arc<dyn Material + Send + Sync> =
+            Arc::new(Lambertian::new_solid_color(Color::RED));
+        let mut model = HittableList::empty();
+        model.add(Sphere::stationary(
+            Point::new(0.0, 0.0, 0.0),
+            1.0,
+            grey.clone(),
+        ));
+        let prototype = Arc::new(BVHTree::from_list(model.objects()));
+
+        let plain = Instance::new(
+            prototype.clone(),
+            Matrix4::translation(Vec3::new(5.0, 0.0, 0.0)),
+        );
+        let scaled = Instance::new(
+            prototype.clone(),
+            Matrix4::translation(Vec3::new(-5.0, 0.0, 0.0))
+                * Matrix4::scaling(Vec3::new(2.0, 2.0, 2.0)),
+        )
+        .with_material(red.clone());
+        assert_eq!(Arc::strong_count(&prototype), 3);
+
+        let ray = |x: f64| Ray {
+            origin: Point::new(x, 0.0, 10.0),
+            dir: Vec3::new(0.0, 0.0, -1.0),
+            time: 0.0,
+        };
+        let hit = plain
+            .hit(&ray(5.0), &Interval::UNIVERSE, &mut RandomSampler)
+            .unwrap();
+        assert!((hit.t - 9.0).abs() < 1e-9);
+        assert!(Arc::ptr_eq(&hit.material, &(grey as Arc<dyn Material>)));
+
+        let hit = scaled
+            .hit(&ray(-5.0), &Interval::UNIVERSE, &mut RandomSampler)
+            .unwrap();
+        assert!((hit.t - 8.0).abs() < 1e-9);
+        assert!((hit.normal - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-9);
+        assert!(Arc::ptr_eq(&hit.material, &(red as Arc<dyn Material>)));
+        assert!(scaled
+            .hit(&ray(5.0), &Interval::UNIVERSE, &mut RandomSampler)
+            .is_none());
+    }
+}
@@ -1,15 +1,27 @@
 mod aabb;
 mod bvh;
+mod constant_medium;
 mod hittable;
 mod hittable_list;
+mod instance;
+mod obj;
 mod quad;
 mod sphere;
+mod tlas;
 mod transform;
+mod triangle;
+mod triangle_mesh;
 
 pub use aabb::Aabb;
-pub use bvh::BVHTree;
+pub use bvh::{BVHOptions, BVHTree, SplitMethod};
+pub use constant_medium::ConstantMedium;
 pub use hittable::{HitRecord, Hittable};
 pub use hittable_list::HittableList;
+pub use instance::Instance;
+pub use obj::ObjModel;
 pub use quad::Quad;
 pub use sphere::Sphere;
-pub use transform::{Transformable, Translate};
+pub use tlas::Tlas;
+pub use transform::{Keyframe, MotionError, MotionTransform, Transform, Transformable, Translate};
+pub use triangle::Triangle;
+pub use triangle_mesh::{MeshBuffers, MeshError, MeshFace, TriangleMesh};
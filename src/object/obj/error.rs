@@ -0,0 +1,41 @@
+use std::{
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+};
+
+/// An error raised while importing an OBJ or MTL file, with the offending line when known.
+#[derive(Debug)]
+pub struct ObjError {
+    path: PathBuf,
+    line: Option<usize>,
+    message: String,
+}
+
+impl ObjError {
+    pub fn new(path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at_line(path: &Path, line: usize, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path.display(), line, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
@@ -0,0 +1,81 @@
+mod error;
+mod mtl;
+mod parser;
+
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use crate::{interval::Interval, material::Material, ray::Ray, sampler::Sampler};
+
+use super::{hittable_list::HittableVec, Aabb, BVHTree, HitRecord, Hittable, TriangleMesh};
+
+pub use error::ObjError;
+use mtl::{parse_mtl, MtlMaterial};
+use parser::parse_obj;
+
+/// Triangle geometry imported from a Wavefront OBJ file, with one mesh per
+/// material sharing a single vertex buffer.
+pub struct ObjModel {
+    tree: BVHTree,
+}
+
+impl ObjModel {
+    /// Loads an OBJ file and the MTL libraries it references. Faces without a
+    /// `usemtl` statement are given `default_material`.
+    pub fn load(
+        path: &Path,
+        default_material: Arc<dyn Material + Send + Sync>,
+    ) -> Result<Self, ObjError> {
+        let source = fs::read_to_string(path)
+            .map_err(|err| ObjError::new(path, format!("cannot read file: {err}")))?;
+        let data = parse_obj(&source, path)?;
+
+        let mut definitions: HashMap<String, MtlMaterial> = HashMap::new();
+        for (lib_path, line) in &data.material_libs {
+            let lib_source = fs::read_to_string(lib_path).map_err(|err| {
+                ObjError::at_line(
+                    path,
+                    *line,
+                    format!("cannot read `{}`: {err}", lib_path.display()),
+                )
+            })?;
+            definitions.extend(parse_mtl(&lib_source, lib_path)?);
+        }
+
+        let buffers = Arc::new(data.buffers);
+        let mut textures = HashMap::new();
+        let mut meshes: HittableVec = Vec::new();
+
+        for (name, faces) in &data.groups {
+            let material = match name {
+                None => default_material.clone(),
+                Some(name) => match definitions.get(name) {
+                    Some(definition) => definition.to_material(path, &mut textures)?,
+                    None => {
+                        return Err(ObjError::at_line(
+                            path,
+                            data.material_lines[name],
+                            format!("unknown material `{name}`"),
+                        ))
+                    }
+                },
+            };
+            let mesh = TriangleMesh::new(buffers.clone(), faces, material)
+                .map_err(|err| ObjError::new(path, err.to_string()))?;
+            meshes.push(Arc::new(mesh));
+        }
+
+        Ok(Self {
+            tree: BVHTree::from_list(&meshes),
+        })
+    }
+}
+
+impl Hittable for ObjModel {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        self.tree.hit(ray, interval, sampler)
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        self.tree.bounding_box()
+    }
+}
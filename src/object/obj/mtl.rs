@@ -0,0 +1,161 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    color::TransferFunction,
+    material::{Dielectric, DiffuseLight, Lambertian, Material, Metal},
+    texture::{ImageTexture, Texture},
+    vec3::Color,
+};
+
+use super::{error::ObjError, parser::parse_floats};
+
+/// The subset of an MTL material definition the renderer can represent.
+pub struct MtlMaterial {
+    kd: Color,
+    ks: Color,
+    ke: Color,
+    ns: f64,
+    ni: Option<f64>,
+    dissolve: f64,
+    illum: u32,
+    map_kd: Option<(PathBuf, usize)>,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        Self {
+            kd: Color::all(0.8),
+            ks: Color::BLACK,
+            ke: Color::BLACK,
+            ns: 0.0,
+            ni: None,
+            dissolve: 1.0,
+            illum: 2,
+            map_kd: None,
+        }
+    }
+}
+
+fn is_black(c: &Color) -> bool {
+    c.x <= 0.0 && c.y <= 0.0 && c.z <= 0.0
+}
+
+impl MtlMaterial {
+    /// Maps the MTL parameters onto the closest built-in material:
+    /// emissive (`Ke`) materials become lights, transparent ones (`d < 1` or a
+    /// refractive `illum`) become dielectrics with index `Ni`, mirror-like ones
+    /// (`illum` 3/5, or only `Ks` set) become metals fuzzed by `Ns`, and the rest
+    /// are Lambertian with `Kd` or `map_Kd`.
+    pub fn to_material(
+        &self,
+        path: &Path,
+        textures: &mut HashMap<PathBuf, Arc<dyn Texture + Send + Sync>>,
+    ) -> Result<Arc<dyn Material + Send + Sync>, ObjError> {
+        if !is_black(&self.ke) {
+            return Ok(Arc::new(DiffuseLight::new_from_color(self.ke)));
+        }
+        if self.dissolve < 1.0 || matches!(self.illum, 4 | 6 | 7 | 9) {
+            return Ok(Arc::new(Dielectric::new(self.ni.unwrap_or(1.5))));
+        }
+        if matches!(self.illum, 3 | 5) || (is_black(&self.kd) && !is_black(&self.ks)) {
+            let fuzz = (2.0 / (self.ns + 2.0)).sqrt();
+            return Ok(Arc::new(Metal::new(self.ks, fuzz)));
+        }
+
+        match &self.map_kd {
+            Some((texture_path, line)) => {
+                let texture = match textures.get(texture_path) {
+                    Some(texture) => texture.clone(),
+                    None => {
+                        let texture: Arc<dyn Texture + Send + Sync> = Arc::new(
+                            ImageTexture::load(texture_path, TransferFunction::Srgb).map_err(
+                                |e| {
+                                    ObjError::at_line(
+                                        path,
+                                        *line,
+                                        format!("cannot load `{}`: {e}", texture_path.display()),
+                                    )
+                                },
+                            )?,
+                        );
+                        textures.insert(texture_path.clone(), texture.clone());
+                        texture
+                    }
+                };
+                Ok(Arc::new(Lambertian::new(texture)))
+            }
+            None => Ok(Arc::new(Lambertian::new_solid_color(self.kd))),
+        }
+    }
+}
+
+pub fn parse_mtl(source: &str, path: &Path) -> Result<HashMap<String, MtlMaterial>, ObjError> {
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.split('#').next().unwrap_or("");
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let err = |message: String| ObjError::at_line(path, line_no, message);
+
+        if keyword == "newmtl" {
+            let name = tokens
+                .next()
+                .ok_or_else(|| err("`newmtl` requires a material name".to_string()))?;
+            if let Some((name, material)) = current.take() {
+                materials.insert(name, material);
+            }
+            current = Some((name.to_string(), MtlMaterial::default()));
+            continue;
+        }
+
+        let Some((_, material)) = current.as_mut() else {
+            return Err(err(format!("`{keyword}` appears before any `newmtl`")));
+        };
+        let color = |tokens: &mut std::str::SplitWhitespace| {
+            let [r, g, b] = parse_floats(tokens, 3, keyword)?;
+            Ok::<_, String>(Color::new(r, g, b))
+        };
+        let scalar = |tokens: &mut std::str::SplitWhitespace| {
+            parse_floats(tokens, 1, keyword).map(|[value, _, _]| value)
+        };
+
+        match keyword {
+            "Kd" => material.kd = color(&mut tokens).map_err(err)?,
+            "Ks" => material.ks = color(&mut tokens).map_err(err)?,
+            "Ke" => material.ke = color(&mut tokens).map_err(err)?,
+            "Ns" => material.ns = scalar(&mut tokens).map_err(err)?,
+            "Ni" => material.ni = Some(scalar(&mut tokens).map_err(err)?),
+            "d" => material.dissolve = scalar(&mut tokens).map_err(err)?,
+            "Tr" => material.dissolve = 1.0 - scalar(&mut tokens).map_err(err)?,
+            "illum" => {
+                let token = tokens.next().unwrap_or("");
+                material.illum = token
+                    .parse()
+                    .map_err(|_| err(format!("invalid illumination model `{token}`")))?;
+            }
+            "map_Kd" => {
+                // texture options precede the file name, which is the last token
+                let file = tokens
+                    .last()
+                    .ok_or_else(|| err("`map_Kd` requires a file name".to_string()))?;
+                material.map_kd = Some((base_dir.join(file), line_no));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((name, material)) = current {
+        materials.insert(name, material);
+    }
+    Ok(materials)
+}
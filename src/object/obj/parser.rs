@@ -0,0 +1,207 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::SplitWhitespace,
+};
+
+use crate::{
+    object::{MeshBuffers, MeshFace},
+    vec3::{Point, Vec3},
+};
+
+use super::error::ObjError;
+
+/// The geometry of an OBJ file, with faces grouped by the material they use.
+pub struct ObjData {
+    pub buffers: MeshBuffers,
+    /// Faces keyed by `usemtl` name; faces before any `usemtl` are keyed by `None`.
+    pub groups: BTreeMap<Option<String>, Vec<MeshFace>>,
+    /// Line of the first `usemtl` for each material, used to report unknown materials.
+    pub material_lines: BTreeMap<String, usize>,
+    /// `mtllib` paths resolved against the OBJ file's directory, with their line.
+    pub material_libs: Vec<(PathBuf, usize)>,
+}
+
+pub fn parse_obj(source: &str, path: &Path) -> Result<ObjData, ObjError> {
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let mut data = ObjData {
+        buffers: MeshBuffers::default(),
+        groups: BTreeMap::new(),
+        material_lines: BTreeMap::new(),
+        material_libs: Vec::new(),
+    };
+    let mut current_material: Option<String> = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.split('#').next().unwrap_or("");
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let err = |message: String| ObjError::at_line(path, line_no, message);
+
+        match keyword {
+            "v" => {
+                let [x, y, z] = parse_floats(&mut tokens, 3, keyword).map_err(err)?;
+                data.buffers.positions.push(Point::new(x, y, z));
+            }
+            "vn" => {
+                let [x, y, z] = parse_floats(&mut tokens, 3, keyword).map_err(err)?;
+                data.buffers.normals.push(Vec3::new(x, y, z));
+            }
+            "vt" => {
+                let [u, v, _] = parse_floats(&mut tokens, 1, keyword).map_err(err)?;
+                data.buffers.uvs.push((u, v));
+            }
+            "f" => {
+                let faces = parse_face(tokens, &data.buffers).map_err(err)?;
+                data.groups
+                    .entry(current_material.clone())
+                    .or_default()
+                    .extend(faces);
+            }
+            "usemtl" => {
+                let name = tokens
+                    .next()
+                    .ok_or_else(|| err("`usemtl` requires a material name".to_string()))?;
+                data.material_lines
+                    .entry(name.to_string())
+                    .or_insert(line_no);
+                current_material = Some(name.to_string());
+            }
+            "mtllib" => {
+                let mut found = false;
+                for lib in tokens {
+                    data.material_libs.push((base_dir.join(lib), line_no));
+                    found = true;
+                }
+                if !found {
+                    return Err(err("`mtllib` requires a file name".to_string()));
+                }
+            }
+            // grouping, smoothing and other statements do not affect rendering
+            _ => {}
+        }
+    }
+
+    Ok(data)
+}
+
+/// Parses between `min` and 3 numbers, filling the missing trailing ones with zero.
+pub(super) fn parse_floats(
+    tokens: &mut SplitWhitespace,
+    min: usize,
+    keyword: &str,
+) -> Result<[f64; 3], String> {
+    let mut values = [0.0; 3];
+    let mut count = 0;
+    for token in tokens.by_ref().take(3) {
+        values[count] = token
+            .parse()
+            .map_err(|_| format!("invalid number `{token}` in `{keyword}`"))?;
+        count += 1;
+    }
+    if count < min {
+        return Err(format!(
+            "`{keyword}` requires at least {min} numbers, found {count}"
+        ));
+    }
+    Ok(values)
+}
+
+/// Resolves a 1-based or negative (relative to the end) OBJ index into a 0-based index.
+fn resolve_index(token: &str, len: usize, kind: &str) -> Result<usize, String> {
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("invalid {kind} index `{token}`"))?;
+    let resolved = match index {
+        i if i > 0 => i - 1,
+        i if i < 0 => len as i64 + i,
+        _ => return Err(format!("{kind} index must not be 0")),
+    };
+    if resolved < 0 || resolved >= len as i64 {
+        return Err(format!(
+            "{kind} index {index} is out of range, {len} defined so far"
+        ));
+    }
+    Ok(resolved as usize)
+}
+
+/// Parses an `f` statement and fan-triangulates it.
+fn parse_face(tokens: SplitWhitespace, buffers: &MeshBuffers) -> Result<Vec<MeshFace>, String> {
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+
+    for token in tokens {
+        let mut parts = token.split('/');
+        let position = parts.next().unwrap_or("");
+        positions.push(resolve_index(position, buffers.positions.len(), "vertex")?);
+
+        match parts.next() {
+            Some("") | None => {}
+            Some(uv) => uvs.push(resolve_index(uv, buffers.uvs.len(), "texture coordinate")?),
+        }
+        match parts.next() {
+            Some("") | None => {}
+            Some(normal) => normals.push(resolve_index(normal, buffers.normals.len(), "normal")?),
+        }
+        if parts.next().is_some() {
+            return Err(format!("malformed face vertex `{token}`"));
+        }
+    }
+
+    if positions.len() < 3 {
+        return Err(format!(
+            "face requires at least 3 vertices, found {}",
+            positions.len()
+        ));
+    }
+    // attributes are only used when every vertex of the face provides them
+    let has_uvs = uvs.len() == positions.len();
+    let has_normals = normals.len() == positions.len();
+
+    Ok((1..positions.len() - 1)
+        .map(|k| {
+            let fan = [0, k, k + 1];
+            MeshFace {
+                positions: fan.map(|i| positions[i]),
+                normals: has_normals.then(|| fan.map(|i| normals[i])),
+                uvs: has_uvs.then(|| fan.map(|i| uvs[i])),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_obj_faces() {
+        let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\nf 1/1/1 2/1/1 3/1/1 4/1/1\nusemtl red\nf -4 -3 -2\n";
+        let data = parse_obj(source, Path::new("test.obj")).expect("valid obj");
+
+        let untextured = &data.groups[&None];
+        assert_eq!(untextured.len(), 2);
+        assert_eq!(untextured[1].positions, [0, 2, 3]);
+        assert_eq!(untextured[0].normals, Some([0, 0, 0]));
+
+        let red = &data.groups[&Some("red".to_string())];
+        assert_eq!(red[0].positions, [0, 1, 2]);
+        assert!(red[0].uvs.is_none());
+    }
+
+    #[test]
+    fn test_malformed_line_reports_line_number() {
+        let source = "v 0 0 0\nv 1 0 0\n\nf 1 2 3\n";
+        let err = parse_obj(source, Path::new("test.obj"))
+            .err()
+            .expect("out of range index should fail");
+        assert_eq!(
+            err.to_string(),
+            "test.obj:4: vertex index 3 is out of range, 2 defined so far"
+        );
+    }
+}
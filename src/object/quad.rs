@@ -4,6 +4,7 @@ use crate::{
     interval::Interval,
     material::Material,
     ray::Ray,
+    sampler::{RandomSampler, Sampler},
     vec3::{Point, Vec3},
 };
 
@@ -16,6 +17,7 @@ pub struct Quad {
     normal: Vec3,
     d: f64,
     w: Vec3,
+    area: f64,
     bbox: Aabb,
     material: Arc<dyn Material + Send + Sync + 'static>,
 }
@@ -38,6 +40,7 @@ impl Quad {
             normal,
             d,
             w,
+            area: n.length(),
             bbox: Aabb::join(&bbox_diag1, &bbox_diag2),
         }
     }
@@ -49,7 +52,12 @@ impl Quad {
 }
 
 impl Hittable for Quad {
-    fn hit(&self, ray: &Ray, hit_interval: &Interval) -> Option<HitRecord> {
+    fn hit(
+        &self,
+        ray: &Ray,
+        hit_interval: &Interval,
+        _sampler: &mut dyn Sampler,
+    ) -> Option<HitRecord> {
         let denom = self.normal.dot(&ray.dir);
         if denom.abs() < 1e-8 {
             return None; // ray is parallel to quad
@@ -79,4 +87,31 @@ impl Hittable for Quad {
     fn bounding_box(&self) -> &Aabb {
         &self.bbox
     }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vec3) -> f64 {
+        let ray = Ray {
+            origin: *origin,
+            dir: *direction,
+            time: 0.0,
+        };
+        // intersecting a quad draws no samples
+        let interval = Interval::new(0.001, f64::INFINITY);
+        let Some(hit) = self.hit(&ray, &interval, &mut RandomSampler) else {
+            return 0.0;
+        };
+
+        let distance_squared = hit.t * hit.t * direction.length_squared();
+        let cosine = (direction.dot(&self.normal) / direction.length()).abs();
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random_direction(&self, origin: &Point, sampler: &mut dyn Sampler) -> Vec3 {
+        let (s, t) = sampler.get_2d();
+        let p = self.q + (s * self.u) + (t * self.v);
+        p - *origin
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.material.is_emissive()
+    }
 }
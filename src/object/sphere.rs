@@ -4,7 +4,8 @@ use crate::{
     interval::Interval,
     material::Material,
     ray::Ray,
-    vec3::{Point, Vec3},
+    sampler::{sample_uniform_sphere, RandomSampler, Sampler},
+    vec3::{Onb, Point, Vec3},
 };
 
 use super::{
@@ -73,8 +74,9 @@ impl Sphere {
 }
 
 impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, ray_t: &Interval) -> Option<HitRecord> {
-        let current_center = self.center.at(ray.time);
+    fn hit(&self, ray: &Ray, ray_t: &Interval, _sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        // the bounding box only covers the motion between times 0 and 1
+        let current_center = self.center.at(ray.time.clamp(0.0, 1.0));
         let oc = current_center - ray.origin;
         let a = ray.dir.length_squared();
         let h = ray.dir.dot(&oc);
@@ -113,4 +115,51 @@ impl Hittable for Sphere {
     fn bounding_box(&self) -> &Aabb {
         &self.bbox
     }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vec3) -> f64 {
+        let ray = Ray {
+            origin: *origin,
+            dir: *direction,
+            time: 0.0,
+        };
+        // intersecting a sphere draws no samples
+        let interval = Interval::new(0.001, f64::INFINITY);
+        if self.hit(&ray, &interval, &mut RandomSampler).is_none() {
+            return 0.0;
+        }
+
+        let distance_squared = (self.center.origin - *origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            // inside the sphere every direction is sampled uniformly
+            return 1.0 / (4.0 * PI);
+        }
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        1.0 / solid_angle
+    }
+
+    /// Samples the cone of directions subtended by the sphere.
+    fn random_direction(&self, origin: &Point, sampler: &mut dyn Sampler) -> Vec3 {
+        let direction = self.center.origin - *origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+        let u = sampler.get_2d();
+        if distance_squared <= radius_squared {
+            return sample_uniform_sphere(u);
+        }
+
+        let (r1, r2) = u;
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * r1;
+        let sin_theta = (1.0 - z * z).sqrt();
+
+        Onb::new(&direction).transform(&Vec3::new(phi.cos() * sin_theta, phi.sin() * sin_theta, z))
+    }
+
+    /// Moving spheres are left out, as light sampling has no ray time to place them at.
+    fn is_emitter(&self) -> bool {
+        self.material.is_emissive() && self.center.dir.length_squared() == 0.0
+    }
 }
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use crate::{interval::Interval, ray::Ray, sampler::Sampler};
+
+use super::{hittable_list::HittableVec, Aabb, BVHOptions, BVHTree, HitRecord, Hittable};
+
+/// Two-level acceleration structure: a top-level BVH over the scene's objects, where each
+/// group, mesh or instance keeps its own bottom-level BVH.
+///
+/// The bottom-level trees are built once. Replacing an object, for example an `Instance`
+/// with a new transform, only rebuilds the top level.
+pub struct Tlas {
+    options: BVHOptions,
+    /// Top-level objects with their bottom-level trees already built
+    objects: HittableVec,
+    top: BVHTree,
+}
+
+impl Tlas {
+    pub fn build(objects: &HittableVec, options: BVHOptions) -> Self {
+        let objects: HittableVec = objects
+            .iter()
+            .map(|object| {
+                object
+                    .accelerated(&options)
+                    .unwrap_or_else(|| object.clone())
+            })
+            .collect();
+        Self {
+            top: BVHTree::build(&objects, options),
+            objects,
+            options,
+        }
+    }
+
+    pub fn objects(&self) -> &HittableVec {
+        &self.objects
+    }
+
+    /// Replaces top-level object `index` and rebuilds the top level.
+    pub fn set(&mut self, index: usize, object: Arc<dyn Hittable + Send + Sync>) {
+        self.objects[index] = object.accelerated(&self.options).unwrap_or(object);
+        self.rebuild();
+    }
+
+    /// Adds a top-level object and rebuilds the top level, returning the object's index.
+    pub fn push(&mut self, object: Arc<dyn Hittable + Send + Sync>) -> usize {
+        self.objects
+            .push(object.accelerated(&self.options).unwrap_or(object));
+        self.rebuild();
+        self.objects.len() - 1
+    }
+
+    fn rebuild(&mut self) {
+        self.top = BVHTree::build(&self.objects, self.options);
+    }
+}
+
+impl Hittable for Tlas {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        self.top.hit(ray, interval, sampler)
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        self.top.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        interval::Interval,
+        material::Lambertian,
+        object::{BVHOptions, BVHTree, Hittable, HittableList, Instance, Transformable},
+        ray::Ray,
+        sampler::RandomSampler,
+        vec3::{Color, Matrix4, Point, Vec3},
+    };
+
+    use super::Tlas;
+
+    #[test]
+    fn nested_lists_get_their_own_tree_and_instances_move_cheaply() {
+        let material = Arc::new(Lambertian::new_solid_color(Color::all(0.5)));
+        let unit_box = HittableList::rectangular_box(
+            &Point::new(0.0, 0.0, 0.0),
+            &Point::new(1.0, 1.0, 1.0),
+            material,
+        );
+        let prototype = Arc::new(BVHTree::from_list(unit_box.objects()));
+
+        let mut world = HittableList::empty();
+        world.add(unit_box.translate(Vec3::new(0.0, 0.0, -5.0)));
+        world.add(Instance::new(prototype.clone(), Matrix4::IDENTITY));
+        let mut tlas = Tlas::build(world.objects(), BVHOptions::default());
+        // the translated list now wraps a bottom-level tree, so it is left alone from now on
+        assert!(tlas.objects()[0]
+            .accelerated(&BVHOptions::default())
+            .is_none());
+
+        let ray = |x: f64| Ray {
+            origin: Point::new(x, 0.5, 10.0),
+            dir: Vec3::new(0.0, 0.0, -1.0),
+            time: 0.0,
+        };
+        assert!(
+            (tlas
+                .hit(&ray(0.5), &Interval::UNIVERSE, &mut RandomSampler)
+                .unwrap()
+                .t
+                - 9.0)
+                .abs()
+                < 1e-9
+        );
+        assert!(tlas
+            .hit(&ray(3.5), &Interval::UNIVERSE, &mut RandomSampler)
+            .is_none());
+
+        let moved = Instance::new(prototype, Matrix4::translation(Vec3::new(3.0, 0.0, 2.0)));
+        tlas.set(1, Arc::new(moved));
+        assert!(
+            (tlas
+                .hit(&ray(3.5), &Interval::UNIVERSE, &mut RandomSampler)
+                .unwrap()
+                .t
+                - 7.0)
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (tlas
+                .hit(&ray(0.5), &Interval::UNIVERSE, &mut RandomSampler)
+                .unwrap()
+                .t
+                - 14.0)
+                .abs()
+                < 1e-9
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use crate::{
+    interval::Interval,
+    object::{Aabb, BVHOptions, HitRecord, Hittable},
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Matrix4, Point, Vec3},
+};
+
+/// Places an object with an arbitrary invertible affine matrix: any combination of rotation,
+/// non-uniform scale, shear and translation.
+pub struct Transform<H: Hittable + Send + Sync> {
+    /// Object to world
+    matrix: Matrix4,
+    /// World to object
+    inverse: Matrix4,
+    /// Inverse transpose, which keeps normals perpendicular to sheared or scaled surfaces
+    normal_matrix: Matrix4,
+    object: H,
+    bbox: Aabb,
+}
+
+impl<H: Hittable + Send + Sync> Transform<H> {
+    /// Panics if `matrix` is singular or not affine (its last row is not `[0, 0, 0, 1]`).
+    pub fn new(matrix: Matrix4, object: H) -> Self {
+        assert!(matrix.is_affine(), "transform matrix is not affine");
+        let inverse = matrix
+            .inverse()
+            .expect("transform matrix is not invertible");
+        Self {
+            matrix,
+            inverse,
+            normal_matrix: inverse.transpose(),
+            bbox: transform_bbox(&matrix, object.bounding_box()),
+            object,
+        }
+    }
+}
+
+/// Smallest box holding the eight transformed corners of `bbox`.
+pub(super) fn transform_bbox(matrix: &Matrix4, bbox: &Aabb) -> Aabb {
+    let mut p_min = Point::INFINITY;
+    let mut p_max = Point::NEG_INFINITY;
+
+    for i in 0..2 {
+        for j in 0..2 {
+            for k in 0..2 {
+                let corner = Point::new(bbox.x[i], bbox.y[j], bbox.z[k]);
+                let tester = matrix.transform_point(&corner);
+
+                for c in 0..3 {
+                    p_min[c] = p_min[c].min(tester[c]);
+                    p_max[c] = p_max[c].max(tester[c]);
+                }
+            }
+        }
+    }
+
+    Aabb::between_points(&p_min, &p_max)
+}
+
+impl<H: Hittable + Send + Sync> Hittable for Transform<H> {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        // the direction is not normalised, so `t` means the same in both spaces
+        let object_ray = Ray {
+            origin: self.inverse.transform_point(&ray.origin),
+            dir: self.inverse.transform_vector(&ray.dir),
+            time: ray.time,
+        };
+
+        let mut hit = self.object.hit(&object_ray, interval, sampler)?;
+        hit.point = self.matrix.transform_point(&hit.point);
+        hit.normal = self.normal_matrix.transform_vector(&hit.normal).to_unit();
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        &self.bbox
+    }
+
+    /// The object's density, rescaled for the matrix stretching directions: a unit direction
+    /// `w` in object space becomes `A w`, and solid angle around it grows by
+    /// `|det A| / |A w|^3`.
+    fn pdf_value(&self, origin: &Point, direction: &Vec3) -> f64 {
+        let object_direction = self.inverse.transform_vector(direction);
+        let pdf = self
+            .object
+            .pdf_value(&self.inverse.transform_point(origin), &object_direction);
+        let stretch = direction.length() / object_direction.length();
+        pdf * stretch.powi(3) / self.matrix.linear_determinant().abs()
+    }
+
+    fn random_direction(&self, origin: &Point, sampler: &mut dyn Sampler) -> Vec3 {
+        let direction = self
+            .object
+            .random_direction(&self.inverse.transform_point(origin), sampler);
+        self.matrix.transform_vector(&direction)
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.object.is_emitter()
+    }
+
+    fn accelerated(&self, options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        Some(Arc::new(Transform {
+            matrix: self.matrix,
+            inverse: self.inverse,
+            normal_matrix: self.normal_matrix,
+            object: self.object.accelerated(options)?,
+            bbox: self.bbox,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f64::consts::PI, sync::Arc};
+
+    use crate::{
+        interval::Interval,
+        material::{DiffuseLight, Lambertian},
+        object::{Hittable, HittableList, Sphere},
+        ray::Ray,
+        sampler::{sample_uniform_sphere, RandomSampler},
+        vec3::{Color, Matrix4, Point, Vec3},
+    };
+
+    use super::Transform;
+
+    #[test]
+    fn scaled_sphere_hits_with_ellipsoid_normals_and_bbox() {
+        let material = Arc::new(Lambertian::new_solid_color(Color::new(0.5, 0.5, 0.5)));
+        let sphere = Sphere::stationary(Point::new(0.0, 0.0, 0.0), 1.0, material);
+        let ellipsoid = Transform::new(
+            Matrix4::translation(Vec3::new(0.0, 0.0, 3.0))
+                * Matrix4::scaling(Vec3::new(2.0, 1.0, 1.0)),
+            sphere,
+        );
+
+        let bbox = ellipsoid.bounding_box();
+        assert!((bbox.x.min + 2.0).abs() < 1e-9 && (bbox.x.max - 2.0).abs() < 1e-9);
+        assert!((bbox.y.max - 1.0).abs() < 1e-9 && (bbox.z.min - 2.0).abs() < 1e-9);
+
+        // x^2 / 4 + y^2 = 1 at y = 0.5 gives x = sqrt(3), where the gradient is (x / 2, 2y, 0)
+        let ray = Ray {
+            origin: Point::new(10.0, 0.5, 3.0),
+            dir: Vec3::new(-1.0, 0.0, 0.0),
+            time: 0.0,
+        };
+        let hit = ellipsoid
+            .hit(
+                &ray,
+                &Interval::new(0.001, f64::INFINITY),
+                &mut RandomSampler,
+            )
+            .expect("ray should hit the ellipsoid");
+        assert!((hit.point.x - 3f64.sqrt()).abs() < 1e-9);
+        assert!((hit.point.z - 3.0).abs() < 1e-9);
+        let expected = Vec3::new(3f64.sqrt() / 2.0, 1.0, 0.0).to_unit();
+        assert!((hit.normal - expected).length() < 1e-9);
+    }
+
+    #[test]
+    fn transformed_lights_are_sampled_with_a_normalised_density() {
+        let light = Arc::new(DiffuseLight::new_from_color(Color::all(4.0)));
+        let sphere = Sphere::stationary(Point::new(0.0, 0.0, 0.0), 1.0, light.clone());
+        let ellipsoid = Transform::new(
+            Matrix4::translation(Vec3::new(0.0, 1.0, -4.0))
+                * Matrix4::rotation_x(30.0)
+                * Matrix4::scaling(Vec3::new(2.0, 1.0, 0.5)),
+            sphere,
+        );
+        let mut world = HittableList::empty();
+        world.add(HittableList::rectangular_box(
+            &Point::new(-1.0, -1.0, -1.0),
+            &Point::new(1.0, 1.0, 1.0),
+            light.clone(),
+        ));
+        world.add(ellipsoid);
+        world.add(Sphere::moving(
+            Point::new(0.0, 5.0, 0.0),
+            Point::new(1.0, 5.0, 0.0),
+            1.0,
+            light,
+        ));
+        let lights = world.lights();
+        assert_eq!(lights.objects().len(), 2);
+
+        // integrating the density over the sphere of directions with a grid of samples
+        let origin = Point::new(0.5, 0.0, 3.0);
+        let ellipsoid = &lights.objects()[1];
+        const STEPS: u32 = 400;
+        let mut total = 0.0;
+        for i in 0..STEPS {
+            for j in 0..STEPS {
+                let u = (
+                    (i as f64 + 0.5) / STEPS as f64,
+                    (j as f64 + 0.5) / STEPS as f64,
+                );
+                total += ellipsoid.pdf_value(&origin, &sample_uniform_sphere(u));
+            }
+        }
+        let integral = total * 4.0 * PI / (STEPS * STEPS) as f64;
+        assert!((integral - 1.0).abs() < 0.02, "{integral}");
+    }
+}
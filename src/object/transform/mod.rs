@@ -1,7 +1,11 @@
+mod affine;
+mod motion;
 mod rotate;
 mod transformable;
 mod translate;
 
+pub use affine::Transform;
+pub use motion::{Keyframe, MotionError, MotionTransform};
 pub use rotate::YRotate;
 pub use transformable::Transformable;
 pub use translate::Translate;
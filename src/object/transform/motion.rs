@@ -0,0 +1,389 @@
+use std::{
+    fmt::{self, Display},
+    sync::Arc,
+};
+
+use crate::{
+    interval::Interval,
+    object::{Aabb, BVHOptions, HitRecord, Hittable},
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Matrix4, Point, Quaternion, Vec3},
+};
+
+use super::affine::transform_bbox;
+
+/// Time steps at which the motion is sampled to bound the swept volume.
+const BBOX_STEPS: usize = 64;
+
+/// An affine transform split into parts that interpolate well: the object is scaled (and
+/// sheared) by `scale`, then rotated by `rotation`, then moved by `translation`.
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub translation: Vec3,
+    pub rotation: Quaternion,
+    pub scale: Matrix4,
+}
+
+impl Keyframe {
+    pub fn new(translation: Vec3, rotation: Quaternion, scale: Vec3) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale: Matrix4::scaling(scale),
+        }
+    }
+
+    /// Splits an affine matrix by polar decomposition of its linear part. A reflection is kept
+    /// in `scale`, as a rotation cannot hold it. Returns `None` if the matrix is singular.
+    pub fn from_matrix(matrix: &Matrix4) -> Option<Self> {
+        let translation = Vec3::new(matrix.m[0][3], matrix.m[1][3], matrix.m[2][3]);
+        let mut linear = *matrix;
+        for row in linear.m.iter_mut().take(3) {
+            row[3] = 0.0;
+        }
+
+        // averaging with the inverse transpose converges to the closest orthogonal matrix
+        let mut rotation = linear;
+        for _ in 0..100 {
+            let inverse_transpose = rotation.inverse()?.transpose();
+            let mut next = rotation;
+            let mut change: f64 = 0.0;
+            for i in 0..3 {
+                for j in 0..3 {
+                    next.m[i][j] = 0.5 * (rotation.m[i][j] + inverse_transpose.m[i][j]);
+                    change = change.max((next.m[i][j] - rotation.m[i][j]).abs());
+                }
+            }
+            rotation = next;
+            if change < 1e-10 {
+                break;
+            }
+        }
+        // a mirrored matrix converges to an improper rotation, which has no quaternion; negating
+        // it makes it proper and moves the reflection into `scale` below
+        if rotation.linear_determinant() < 0.0 {
+            for row in rotation.m.iter_mut().take(3) {
+                for value in row.iter_mut().take(3) {
+                    *value = -*value;
+                }
+            }
+        }
+
+        Some(Self {
+            translation,
+            rotation: Quaternion::from_matrix(&rotation),
+            scale: rotation.transpose() * linear,
+        })
+    }
+
+    pub fn matrix(&self) -> Matrix4 {
+        Matrix4::translation(self.translation) * self.rotation.to_matrix() * self.scale
+    }
+
+    /// Blends towards `other`, with `t` in [0, 1]. Rotation is interpolated with slerp.
+    pub fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            translation: self.translation + t * (other.translation - self.translation),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.interpolate_scale(other, t),
+        }
+    }
+
+    fn interpolate_scale(&self, other: &Self, t: f64) -> Matrix4 {
+        let mut scale = self.scale;
+        for (row, other_row) in scale.m.iter_mut().zip(&other.scale.m) {
+            for (value, other_value) in row.iter_mut().zip(other_row) {
+                *value += t * (other_value - *value);
+            }
+        }
+        scale
+    }
+}
+
+/// Whether the scale blended between two keyframes is invertible at every time. Its
+/// determinant is a cubic in time, so it is enough that the cubic has the same sign at both
+/// ends and at its turning points.
+fn scale_stays_invertible(start: &Keyframe, end: &Keyframe) -> bool {
+    let det = |t: f64| start.interpolate_scale(end, t).linear_determinant();
+    // the exact cubic in s = 3t from its values at s = 0, 1, 2, 3, by forward differences
+    let p = [det(0.0), det(1.0 / 3.0), det(2.0 / 3.0), det(1.0)];
+    let d1 = p[1] - p[0];
+    let d2 = p[2] - 2.0 * p[1] + p[0];
+    let d3 = p[3] - 3.0 * p[2] + 3.0 * p[1] - p[0];
+    let (c1, c2, c3) = (d1 - d2 / 2.0 + d3 / 3.0, (d2 - d3) / 2.0, d3 / 6.0);
+    let cubic = |s: f64| p[0] + s * (c1 + s * (c2 + s * c3));
+
+    // roots of the derivative c1 + 2 c2 s + 3 c3 s^2
+    let mut turning_points = Vec::new();
+    let (a, b, c) = (3.0 * c3, 2.0 * c2, c1);
+    if a.abs() < 1e-12 * (b.abs() + c.abs()) {
+        if b != 0.0 {
+            turning_points.push(-c / b);
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant >= 0.0 {
+            let root = discriminant.sqrt();
+            turning_points.extend([(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]);
+        }
+    }
+
+    let tolerance = 1e-12 * p[0].abs().max(p[3].abs());
+    [0.0, 3.0]
+        .into_iter()
+        .chain(
+            turning_points
+                .into_iter()
+                .filter(|s| (0.0..=3.0).contains(s)),
+        )
+        .all(|s| cubic(s) * p[0].signum() > tolerance)
+}
+
+/// Inverse of the linear part of `m` by cofactors, for a scale known to be invertible.
+fn inverse_scale(m: &Matrix4) -> Matrix4 {
+    let det = m.linear_determinant();
+    let a = &m.m;
+    let mut inverse = Matrix4::IDENTITY;
+    for (i, row) in inverse.m.iter_mut().take(3).enumerate() {
+        for (j, value) in row.iter_mut().take(3).enumerate() {
+            let (r0, r1, c0, c1) = ((j + 1) % 3, (j + 2) % 3, (i + 1) % 3, (i + 2) % 3);
+            *value = (a[r0][c0] * a[r1][c1] - a[r0][c1] * a[r1][c0]) / det;
+        }
+    }
+    inverse
+}
+
+/// Moves an object from the `start` transform at time 0 to the `end` transform at time 1,
+/// following the time of each ray so that the motion is blurred over the shutter. Before and
+/// after that it holds its first and last pose.
+pub struct MotionTransform<H: Hittable + Send + Sync> {
+    start: Keyframe,
+    end: Keyframe,
+    object: H,
+    bbox: Aabb,
+}
+
+impl<H: Hittable + Send + Sync> MotionTransform<H> {
+    /// Fails if the scale becomes singular at some time between the keyframes, for example
+    /// when only one of them is mirrored.
+    pub fn new(start: Keyframe, end: Keyframe, object: H) -> Result<Self, MotionError> {
+        if !scale_stays_invertible(&start, &end) {
+            return Err(MotionError::new("motion passes through a singular scale"));
+        }
+        Ok(Self {
+            bbox: swept_bbox(&start, &end, object.bounding_box()),
+            start,
+            end,
+            object,
+        })
+    }
+
+    /// Fails if either matrix is singular, or the motion between them is, see `new`.
+    pub fn from_matrices(start: &Matrix4, end: &Matrix4, object: H) -> Result<Self, MotionError> {
+        let keyframe = |matrix| {
+            Keyframe::from_matrix(matrix)
+                .ok_or_else(|| MotionError::new("transform is not invertible"))
+        };
+        Self::new(keyframe(start)?, keyframe(end)?, object)
+    }
+}
+
+/// Keyframes that cannot be blended into a motion.
+#[derive(Debug)]
+pub struct MotionError {
+    message: String,
+}
+
+impl MotionError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for MotionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for MotionError {}
+
+/// Box around everything `bbox` passes through between the two keyframes. Translation and
+/// scale move corners along straight lines, so the boxes at evenly spaced times only miss the
+/// bulge of the rotation between two steps, which is added as padding.
+fn swept_bbox(start: &Keyframe, end: &Keyframe, bbox: &Aabb) -> Aabb {
+    let mut swept = Aabb::EMPTY;
+    for step in 0..=BBOX_STEPS {
+        let t = step as f64 / BBOX_STEPS as f64;
+        let matrix = start.interpolate(end, t).matrix();
+        swept = Aabb::join(&swept, &transform_bbox(&matrix, bbox));
+    }
+
+    let step_angle = start.rotation.angle_to(&end.rotation) / BBOX_STEPS as f64;
+    let mut radius: f64 = 0.0;
+    for x in [bbox.x.min, bbox.x.max] {
+        for y in [bbox.y.min, bbox.y.max] {
+            for z in [bbox.z.min, bbox.z.max] {
+                let corner = Point::new(x, y, z);
+                for keyframe in [start, end] {
+                    radius = radius.max(keyframe.scale.transform_vector(&corner).length());
+                }
+            }
+        }
+    }
+    // twice the sagitta of the arc between steps, leaving room for scale changing meanwhile
+    let padding = 2.0 * radius * (1.0 - (step_angle / 2.0).cos());
+    Aabb::new(
+        swept.x.expand(2.0 * padding),
+        swept.y.expand(2.0 * padding),
+        swept.z.expand(2.0 * padding),
+    )
+}
+
+impl<H: Hittable + Send + Sync> Hittable for MotionTransform<H> {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        let time = ray.time.clamp(0.0, 1.0);
+        let keyframe = self.start.interpolate(&self.end, time);
+        // undo translation, rotation and scale in turn rather than inverting the whole matrix
+        let inverse_rotation = keyframe.rotation.conjugate();
+        let inverse_scale = inverse_scale(&keyframe.scale);
+        let object_ray = Ray {
+            origin: inverse_scale
+                .transform_vector(&inverse_rotation.rotate(&(ray.origin - keyframe.translation))),
+            dir: inverse_scale.transform_vector(&inverse_rotation.rotate(&ray.dir)),
+            time: ray.time,
+        };
+
+        let mut hit = self.object.hit(&object_ray, interval, sampler)?;
+        hit.point = keyframe
+            .rotation
+            .rotate(&keyframe.scale.transform_vector(&hit.point))
+            + keyframe.translation;
+        hit.normal = keyframe
+            .rotation
+            .rotate(&inverse_scale.transpose().transform_vector(&hit.normal))
+            .to_unit();
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        &self.bbox
+    }
+
+    fn accelerated(&self, options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        Some(Arc::new(MotionTransform {
+            start: self.start,
+            end: self.end,
+            object: self.object.accelerated(options)?,
+            bbox: self.bbox,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        interval::Interval,
+        material::Lambertian,
+        object::{Hittable, HittableList},
+        ray::Ray,
+        sampler::RandomSampler,
+        vec3::{Color, Matrix4, Point, Quaternion, Vec3},
+    };
+
+    use super::{Keyframe, MotionTransform};
+
+    #[test]
+    fn decomposition_round_trips_and_slerp_keeps_the_rotation_rigid() {
+        let matrix = Matrix4::translation(Vec3::new(1.0, 2.0, 3.0))
+            * Matrix4::rotation(Vec3::new(1.0, 2.0, 0.5), 70.0)
+            * Matrix4::scaling(Vec3::new(2.0, 1.0, 0.5));
+        let keyframe = Keyframe::from_matrix(&matrix).unwrap();
+        let p = Point::new(0.3, -0.2, 0.9);
+        let error = keyframe.matrix().transform_point(&p) - matrix.transform_point(&p);
+        assert!(error.length() < 1e-9);
+
+        let mirrored = Matrix4::translation(Vec3::new(-1.0, 0.0, 2.0))
+            * Matrix4::rotation_z(30.0)
+            * Matrix4::scaling(Vec3::new(-1.0, 1.0, 1.0));
+        let keyframe = Keyframe::from_matrix(&mirrored).unwrap();
+        let error = keyframe.matrix().transform_point(&p) - mirrored.transform_point(&p);
+        assert!(error.length() < 1e-9);
+        assert!(keyframe.scale.linear_determinant() < 0.0);
+
+        let start = Keyframe::new(Vec3::ZERO, Quaternion::IDENTITY, Vec3::all(1.0));
+        let end = Keyframe::new(
+            Vec3::ZERO,
+            Quaternion::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 90.0),
+            Vec3::all(1.0),
+        );
+        let halfway = start.interpolate(&end, 0.5).matrix();
+        let expected = Matrix4::rotation_y(45.0);
+        let v = Vec3::new(1.0, 0.0, 0.0);
+        let error = halfway.transform_vector(&v) - expected.transform_vector(&v);
+        assert!(error.length() < 1e-9);
+    }
+
+    #[test]
+    fn swept_box_contains_the_object_at_every_time() {
+        let material = Arc::new(Lambertian::new_solid_color(Color::all(0.5)));
+        let cube = HittableList::rectangular_box(
+            &Point::new(1.0, -0.5, -0.5),
+            &Point::new(2.0, 0.5, 0.5),
+            material,
+        );
+        let spinning = MotionTransform::new(
+            Keyframe::new(Vec3::ZERO, Quaternion::IDENTITY, Vec3::all(1.0)),
+            Keyframe::new(
+                Vec3::new(0.0, 1.0, 0.0),
+                Quaternion::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 180.0),
+                Vec3::all(1.0),
+            ),
+            cube,
+        )
+        .unwrap();
+        let bbox = *spinning.bounding_box();
+        // the far corner sweeps a half circle of radius sqrt(2^2 + 0.5^2) around the y axis
+        assert!(bbox.z.min < -2.06 && bbox.x.min <= -2.0 && bbox.y.max >= 1.5);
+
+        // a ray down the z axis at time 0.5 hits the cube, turned 90 degrees and raised by 0.5
+        let ray = Ray {
+            origin: Point::new(0.0, 0.5, 10.0),
+            dir: Vec3::new(0.0, 0.0, -1.0),
+            time: 0.5,
+        };
+        let hit = spinning
+            .hit(&ray, &Interval::UNIVERSE, &mut RandomSampler)
+            .unwrap();
+        assert!((hit.point.z + 1.0).abs() < 1e-9);
+        assert!((hit.normal - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-9);
+        let ray = Ray { time: 0.0, ..ray };
+        assert!(spinning
+            .hit(&ray, &Interval::UNIVERSE, &mut RandomSampler)
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_motion_through_a_singular_scale() {
+        let material = Arc::new(Lambertian::new_solid_color(Color::all(0.5)));
+        let cube = HittableList::rectangular_box(
+            &Point::new(0.0, 0.0, 0.0),
+            &Point::new(1.0, 1.0, 1.0),
+            material,
+        );
+        // both ends have a positive determinant, but x and y pass through zero halfway
+        let err = MotionTransform::new(
+            Keyframe::new(Vec3::ZERO, Quaternion::IDENTITY, Vec3::all(1.0)),
+            Keyframe::new(Vec3::ZERO, Quaternion::IDENTITY, Vec3::new(-1.0, -1.0, 1.0)),
+            cube,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("singular scale"));
+    }
+}
@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use crate::{
     interval::Interval,
-    object::{Aabb, HitRecord, Hittable},
+    object::{Aabb, BVHOptions, HitRecord, Hittable},
     ray::Ray,
+    sampler::Sampler,
     vec3::{Point, Vec3},
 };
 
@@ -56,45 +57,35 @@ impl<H: Hittable + Send + Sync> YRotate<H> {
     }
 }
 
-impl<H: Hittable + Send + Sync> Hittable for YRotate<H> {
-    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord> {
-        let Ray { origin, dir, time } = ray;
-        let Self {
-            sin_theta,
-            cos_theta,
-            ..
-        } = self;
-
-        let rotated_origin = Point::new(
-            cos_theta * origin.x - sin_theta * origin.z,
-            origin.y,
-            sin_theta * origin.x + cos_theta * origin.z,
-        );
+impl<H: Hittable + Send + Sync> YRotate<H> {
+    fn to_object(&self, v: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x - self.sin_theta * v.z,
+            v.y,
+            self.sin_theta * v.x + self.cos_theta * v.z,
+        )
+    }
 
-        let rotated_dir = Vec3::new(
-            cos_theta * dir.x - sin_theta * dir.z,
-            dir.y,
-            sin_theta * dir.x + cos_theta * dir.z,
-        );
+    fn to_world(&self, v: &Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x + self.sin_theta * v.z,
+            v.y,
+            -self.sin_theta * v.x + self.cos_theta * v.z,
+        )
+    }
+}
 
+impl<H: Hittable + Send + Sync> Hittable for YRotate<H> {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
         let rotated_ray = Ray {
-            origin: rotated_origin,
-            dir: rotated_dir,
-            time: *time,
+            origin: self.to_object(&ray.origin),
+            dir: self.to_object(&ray.dir),
+            time: ray.time,
         };
 
-        if let Some(mut hit) = self.object.hit(&rotated_ray, interval) {
-            hit.point = Point::new(
-                cos_theta * hit.point.x + sin_theta * hit.point.z,
-                hit.point.y,
-                -sin_theta * hit.point.x + cos_theta * hit.point.z,
-            );
-
-            hit.normal = Vec3::new(
-                cos_theta * hit.normal.x + sin_theta * hit.normal.z,
-                hit.normal.y,
-                -sin_theta * hit.normal.x + cos_theta * hit.normal.z,
-            );
+        if let Some(mut hit) = self.object.hit(&rotated_ray, interval, sampler) {
+            hit.point = self.to_world(&hit.point);
+            hit.normal = self.to_world(&hit.normal);
             return Some(hit);
         }
 
@@ -104,4 +95,29 @@ impl<H: Hittable + Send + Sync> Hittable for YRotate<H> {
     fn bounding_box(&self) -> &Aabb {
         &self.bbox
     }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vec3) -> f64 {
+        self.object
+            .pdf_value(&self.to_object(origin), &self.to_object(direction))
+    }
+
+    fn random_direction(&self, origin: &Point, sampler: &mut dyn Sampler) -> Vec3 {
+        let direction = self
+            .object
+            .random_direction(&self.to_object(origin), sampler);
+        self.to_world(&direction)
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.object.is_emitter()
+    }
+
+    fn accelerated(&self, options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        Some(Arc::new(YRotate {
+            sin_theta: self.sin_theta,
+            cos_theta: self.cos_theta,
+            object: self.object.accelerated(options)?,
+            bbox: self.bbox,
+        }))
+    }
 }
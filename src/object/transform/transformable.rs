@@ -1,10 +1,26 @@
-use crate::{object::Hittable, vec3::Vec3};
+use crate::{
+    object::Hittable,
+    vec3::{Matrix4, Vec3},
+};
 
-use super::{Translate, YRotate};
+use super::{MotionError, MotionTransform, Transform, Translate, YRotate};
 
 pub trait Transformable<T: Hittable + Send + Sync> {
     fn translate(self, offset: Vec3) -> Translate<T>;
     fn rotate_y(self, angle: f64) -> YRotate<T>;
+    fn rotate_x(self, angle: f64) -> Transform<T>;
+    fn rotate_z(self, angle: f64) -> Transform<T>;
+    /// Scales by a separate factor along each axis, about the origin.
+    fn scale(self, factors: Vec3) -> Transform<T>;
+    /// Applies an arbitrary invertible affine matrix.
+    fn transform(self, matrix: Matrix4) -> Transform<T>;
+    /// Moves from the `start` matrix at time 0 to the `end` matrix at time 1, blurring the
+    /// motion over the shutter. Fails if either matrix or the motion between them is singular.
+    fn transform_moving(
+        self,
+        start: Matrix4,
+        end: Matrix4,
+    ) -> Result<MotionTransform<T>, MotionError>;
 }
 
 impl<T> Transformable<T> for T
@@ -18,4 +34,28 @@ where
     fn rotate_y(self, angle: f64) -> YRotate<T> {
         YRotate::new(angle, self)
     }
+
+    fn rotate_x(self, angle: f64) -> Transform<T> {
+        Transform::new(Matrix4::rotation_x(angle), self)
+    }
+
+    fn rotate_z(self, angle: f64) -> Transform<T> {
+        Transform::new(Matrix4::rotation_z(angle), self)
+    }
+
+    fn scale(self, factors: Vec3) -> Transform<T> {
+        Transform::new(Matrix4::scaling(factors), self)
+    }
+
+    fn transform(self, matrix: Matrix4) -> Transform<T> {
+        Transform::new(matrix, self)
+    }
+
+    fn transform_moving(
+        self,
+        start: Matrix4,
+        end: Matrix4,
+    ) -> Result<MotionTransform<T>, MotionError> {
+        MotionTransform::from_matrices(&start, &end, self)
+    }
 }
@@ -2,9 +2,10 @@ use std::sync::Arc;
 
 use crate::{
     interval::Interval,
-    object::{Aabb, HitRecord, Hittable},
+    object::{Aabb, BVHOptions, HitRecord, Hittable},
     ray::Ray,
-    vec3::Vec3,
+    sampler::Sampler,
+    vec3::{Point, Vec3},
 };
 
 pub struct Translate<H: Hittable + Send + Sync> {
@@ -24,14 +25,14 @@ impl<H: Hittable + Send + Sync> Translate<H> {
 }
 
 impl<H: Hittable + Send + Sync> Hittable for Translate<H> {
-    fn hit(&self, ray: &Ray, interval: &Interval) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
         let offset_ray = Ray {
             origin: ray.origin - self.offset,
             dir: ray.dir,
             time: ray.time,
         };
 
-        if let Some(mut hit) = self.object.hit(&offset_ray, interval) {
+        if let Some(mut hit) = self.object.hit(&offset_ray, interval, sampler) {
             hit.point += self.offset;
             return Some(hit);
         }
@@ -42,4 +43,22 @@ impl<H: Hittable + Send + Sync> Hittable for Translate<H> {
     fn bounding_box(&self) -> &Aabb {
         &self.bbox
     }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vec3) -> f64 {
+        self.object.pdf_value(&(*origin - self.offset), direction)
+    }
+
+    fn random_direction(&self, origin: &Point, sampler: &mut dyn Sampler) -> Vec3 {
+        self.object
+            .random_direction(&(*origin - self.offset), sampler)
+    }
+
+    fn is_emitter(&self) -> bool {
+        self.object.is_emitter()
+    }
+
+    fn accelerated(&self, options: &BVHOptions) -> Option<Arc<dyn Hittable + Send + Sync>> {
+        let object = self.object.accelerated(options)?;
+        Some(Arc::new(Translate::new(self.offset, object)))
+    }
 }
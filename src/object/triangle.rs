@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use crate::{
+    interval::Interval,
+    material::Material,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Point, Vec3},
+};
+
+use super::{Aabb, HitRecord, Hittable};
+
+pub type TexCoord = (f64, f64);
+
+pub struct Triangle {
+    vertices: [Point; 3],
+    normals: Option<[Vec3; 3]>,
+    uvs: Option<[TexCoord; 3]>,
+    bbox: Aabb,
+    material: Arc<dyn Material + Send + Sync>,
+}
+
+impl Triangle {
+    pub fn new(vertices: [Point; 3], material: Arc<dyn Material + Send + Sync>) -> Self {
+        Self::with_attributes(vertices, None, None, material)
+    }
+
+    pub fn with_attributes(
+        vertices: [Point; 3],
+        normals: Option<[Vec3; 3]>,
+        uvs: Option<[TexCoord; 3]>,
+        material: Arc<dyn Material + Send + Sync>,
+    ) -> Self {
+        Self {
+            bbox: triangle_bbox(&vertices),
+            vertices,
+            normals,
+            uvs,
+            material,
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, interval: &Interval, _sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        let (t, b1, b2) = intersect(&self.vertices, ray, interval)?;
+        Some(shade(
+            ray,
+            t,
+            (b1, b2),
+            &self.vertices,
+            self.normals.as_ref(),
+            self.uvs.as_ref(),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        &self.bbox
+    }
+}
+
+pub(super) fn triangle_bbox(vertices: &[Point; 3]) -> Aabb {
+    Aabb::join(
+        &Aabb::between_points(&vertices[0], &vertices[1]),
+        &Aabb::between_points(&vertices[0], &vertices[2]),
+    )
+}
+
+/// Möller–Trumbore intersection, returning the hit distance and the barycentric
+/// coordinates of the hit point relative to the second and third vertex.
+pub(super) fn intersect(
+    vertices: &[Point; 3],
+    ray: &Ray,
+    interval: &Interval,
+) -> Option<(f64, f64, f64)> {
+    let edge1 = vertices[1] - vertices[0];
+    let edge2 = vertices[2] - vertices[0];
+
+    let p = ray.dir.cross(&edge2);
+    let det = edge1.dot(&p);
+    if det.abs() < 1e-12 {
+        return None; // ray is parallel to the triangle
+    }
+    let inv_det = 1.0 / det;
+
+    let s = ray.origin - vertices[0];
+    let b1 = s.dot(&p) * inv_det;
+    if !(0.0..=1.0).contains(&b1) {
+        return None;
+    }
+
+    let q = s.cross(&edge1);
+    let b2 = ray.dir.dot(&q) * inv_det;
+    if b2 < 0.0 || b1 + b2 > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(&q) * inv_det;
+    if !interval.surrounds(t) {
+        return None;
+    }
+    Some((t, b1, b2))
+}
+
+/// Builds the hit record for a triangle hit, interpolating the optional per-vertex
+/// normals and texture coordinates with the barycentric coordinates of the hit.
+pub(super) fn shade(
+    ray: &Ray,
+    t: f64,
+    (b1, b2): (f64, f64),
+    vertices: &[Point; 3],
+    normals: Option<&[Vec3; 3]>,
+    uvs: Option<&[TexCoord; 3]>,
+    material: Arc<dyn Material + Send + Sync>,
+) -> HitRecord {
+    let b0 = 1.0 - b1 - b2;
+    let geometric_normal = (vertices[1] - vertices[0])
+        .cross(&(vertices[2] - vertices[0]))
+        .to_unit();
+
+    let (u, v) = match uvs {
+        Some([uv0, uv1, uv2]) => (
+            b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+            b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+        ),
+        None => (b1, b2),
+    };
+
+    let mut record = HitRecord::new(ray, t, geometric_normal, u, v, material);
+
+    if let Some([n0, n1, n2]) = normals {
+        let shading_normal = (b0 * n0 + b1 * n1 + b2 * n2).to_unit();
+        // keep the interpolated normal on the same side as the geometric one
+        record.normal = if shading_normal.dot(&record.normal) < 0.0 {
+            -shading_normal
+        } else {
+            shading_normal
+        };
+    }
+
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::Lambertian, sampler::RandomSampler};
+
+    #[test]
+    fn test_triangle_hit_uv() {
+        let triangle = Triangle::with_attributes(
+            [
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            None,
+            Some([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]),
+            Arc::new(Lambertian::new_solid_color(Vec3::all(0.5))),
+        );
+
+        let ray = Ray {
+            origin: Point::new(0.25, 0.5, 1.0),
+            dir: Vec3::new(0.0, 0.0, -1.0),
+            time: 0.0,
+        };
+        let hit = triangle
+            .hit(&ray, &Interval::POSITIVE, &mut RandomSampler)
+            .expect("ray should hit the triangle");
+        assert!((hit.t - 1.0).abs() < 1e-9);
+        assert!((hit.u - 0.25).abs() < 1e-9 && (hit.v - 0.5).abs() < 1e-9);
+        assert!(hit.front_face);
+
+        let miss = Ray {
+            origin: Point::new(0.75, 0.75, 1.0),
+            ..ray
+        };
+        assert!(triangle
+            .hit(&miss, &Interval::POSITIVE, &mut RandomSampler)
+            .is_none());
+    }
+}
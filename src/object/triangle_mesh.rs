@@ -0,0 +1,203 @@
+use std::{
+    fmt::{self, Display},
+    sync::Arc,
+};
+
+use crate::{
+    interval::Interval,
+    material::Material,
+    ray::Ray,
+    sampler::Sampler,
+    vec3::{Point, Vec3},
+};
+
+use super::{
+    hittable_list::HittableVec,
+    triangle::{intersect, shade, triangle_bbox, TexCoord},
+    Aabb, BVHTree, HitRecord, Hittable,
+};
+
+/// Vertex attribute buffers shared by every triangle of one or more meshes.
+#[derive(Default)]
+pub struct MeshBuffers {
+    pub positions: Vec<Point>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<TexCoord>,
+}
+
+/// A triangle of a mesh, described by indices into the shared `MeshBuffers`.
+#[derive(Clone, Copy, Debug)]
+pub struct MeshFace {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+}
+
+struct MeshTriangle {
+    buffers: Arc<MeshBuffers>,
+    face: MeshFace,
+    bbox: Aabb,
+    material: Arc<dyn Material + Send + Sync>,
+}
+
+impl MeshTriangle {
+    fn vertices(&self) -> [Point; 3] {
+        self.face.positions.map(|i| self.buffers.positions[i])
+    }
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, interval: &Interval, _sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        let vertices = self.vertices();
+        let (t, b1, b2) = intersect(&vertices, ray, interval)?;
+
+        let normals = self
+            .face
+            .normals
+            .map(|n| n.map(|i| self.buffers.normals[i]));
+        let uvs = self.face.uvs.map(|uv| uv.map(|i| self.buffers.uvs[i]));
+        Some(shade(
+            ray,
+            t,
+            (b1, b2),
+            &vertices,
+            normals.as_ref(),
+            uvs.as_ref(),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        &self.bbox
+    }
+}
+
+/// An indexed triangle mesh with its own BVH over its faces.
+pub struct TriangleMesh {
+    tree: BVHTree,
+}
+
+impl TriangleMesh {
+    /// Fails if there are no faces or a face refers to a missing vertex attribute.
+    pub fn new(
+        buffers: Arc<MeshBuffers>,
+        faces: &[MeshFace],
+        material: Arc<dyn Material + Send + Sync>,
+    ) -> Result<Self, MeshError> {
+        if faces.is_empty() {
+            return Err(MeshError::new(None, "mesh has no faces"));
+        }
+        let mut triangles: HittableVec = Vec::with_capacity(faces.len());
+        for (index, face) in faces.iter().enumerate() {
+            Self::check_face(&buffers, face)
+                .map_err(|message| MeshError::new(Some(index), message))?;
+            let bbox = triangle_bbox(&face.positions.map(|i| buffers.positions[i]));
+            triangles.push(Arc::new(MeshTriangle {
+                buffers: buffers.clone(),
+                face: *face,
+                bbox,
+                material: material.clone(),
+            }));
+        }
+
+        Ok(Self {
+            tree: BVHTree::from_list(&triangles),
+        })
+    }
+
+    fn check_face(buffers: &MeshBuffers, face: &MeshFace) -> Result<(), &'static str> {
+        let in_range = |indices: &[usize; 3], len: usize| indices.iter().all(|&i| i < len);
+        if !in_range(&face.positions, buffers.positions.len()) {
+            return Err("references a missing position");
+        }
+        if !face
+            .normals
+            .is_none_or(|n| in_range(&n, buffers.normals.len()))
+        {
+            return Err("references a missing normal");
+        }
+        if !face.uvs.is_none_or(|uv| in_range(&uv, buffers.uvs.len())) {
+            return Err("references a missing texture coordinate");
+        }
+        Ok(())
+    }
+}
+
+/// A mesh that cannot be built from its faces, naming the offending face when there is one.
+#[derive(Debug)]
+pub struct MeshError {
+    face: Option<usize>,
+    message: String,
+}
+
+impl MeshError {
+    fn new(face: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            face,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.face {
+            Some(face) => write!(f, "face {}: {}", face, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, interval: &Interval, sampler: &mut dyn Sampler) -> Option<HitRecord> {
+        self.tree.hit(ray, interval, sampler)
+    }
+
+    fn bounding_box(&self) -> &Aabb {
+        self.tree.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        material::Lambertian,
+        vec3::{Color, Point, Vec3},
+    };
+
+    use super::{MeshBuffers, MeshFace, TriangleMesh};
+
+    #[test]
+    fn rejects_faces_with_missing_attributes() {
+        let buffers = Arc::new(MeshBuffers {
+            positions: vec![
+                Point::new(0.0, 0.0, 0.0),
+                Point::new(1.0, 0.0, 0.0),
+                Point::new(0.0, 1.0, 0.0),
+            ],
+            normals: vec![Vec3::new(0.0, 0.0, 1.0)],
+            uvs: Vec::new(),
+        });
+        let material = Arc::new(Lambertian::new_solid_color(Color::all(0.5)));
+        let face = MeshFace {
+            positions: [0, 1, 2],
+            normals: Some([0, 0, 0]),
+            uvs: None,
+        };
+        assert!(TriangleMesh::new(buffers.clone(), &[face], material.clone()).is_ok());
+
+        let bad_normal = MeshFace {
+            normals: Some([0, 0, 1]),
+            ..face
+        };
+        let err = TriangleMesh::new(buffers.clone(), &[face, bad_normal], material.clone())
+            .err()
+            .expect("missing normal should fail");
+        assert_eq!(err.to_string(), "face 1: references a missing normal");
+        assert!(TriangleMesh::new(buffers, &[], material).is_err());
+    }
+}
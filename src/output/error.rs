@@ -0,0 +1,28 @@
+use std::{
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+};
+
+/// An error raised while choosing a format for, or writing, the output image.
+#[derive(Debug)]
+pub struct OutputError {
+    path: PathBuf,
+    message: String,
+}
+
+impl OutputError {
+    pub fn new(path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for OutputError {}
@@ -0,0 +1,194 @@
+mod error;
+mod pfm;
+mod tone_map;
+
+use std::{fs, path::Path};
+
+use image::{ImageBuffer, ImageFormat, Rgb, RgbImage};
+
+use crate::{color::ColorSpace, vec3::Color};
+
+pub use error::OutputError;
+pub use tone_map::{ToneMapper, ToneMapping};
+
+/// File formats the final image can be written in.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OutputFormat {
+    /// An 8-bit display-referred format: PNG, JPEG, BMP or TIFF
+    Ldr(ImageFormat),
+    /// OpenEXR with 32-bit float channels
+    Exr,
+    /// Radiance RGBE
+    Hdr,
+    /// Portable float map
+    Pfm,
+}
+
+impl OutputFormat {
+    /// Infers the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, OutputError> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .ok_or_else(|| {
+                OutputError::new(
+                    path,
+                    "missing file extension, cannot infer the image format",
+                )
+            })?;
+        if extension == "pfm" {
+            return Ok(OutputFormat::Pfm);
+        }
+
+        match ImageFormat::from_extension(&extension) {
+            Some(ImageFormat::OpenExr) => Ok(OutputFormat::Exr),
+            Some(ImageFormat::Hdr) => Ok(OutputFormat::Hdr),
+            Some(
+                format @ (ImageFormat::Png
+                | ImageFormat::Jpeg
+                | ImageFormat::Bmp
+                | ImageFormat::Tiff),
+            ) => Ok(OutputFormat::Ldr(format)),
+            _ => Err(OutputError::new(
+                path,
+                format!("unsupported image format `.{extension}`"),
+            )),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Ldr(format) => format.extensions_str()[0],
+            OutputFormat::Exr => "exr",
+            OutputFormat::Hdr => "hdr",
+            OutputFormat::Pfm => "pfm",
+        }
+    }
+}
+
+/// Writes a row-major buffer of linear Rec.709 radiance in `color_space`, creating missing
+/// parent directories. Float formats only convert primaries and keep values linear and
+/// unclamped, 8-bit formats are tone mapped and encoded with the space's transfer curve.
+pub fn save_image(
+    path: &Path,
+    format: OutputFormat,
+    width: u32,
+    height: u32,
+    pixels: &[Color],
+    tone_mapping: &ToneMapping,
+    color_space: ColorSpace,
+) -> Result<(), OutputError> {
+    assert_eq!(pixels.len(), (width * height) as usize);
+    create_parent_dir(path)?;
+
+    let result = match format {
+        OutputFormat::Ldr(format) => {
+            display_image(width, height, pixels, tone_mapping, color_space)
+                .save_with_format(path, format)
+        }
+        OutputFormat::Exr => float_image(width, height, pixels, color_space)
+            .save_with_format(path, ImageFormat::OpenExr),
+        OutputFormat::Hdr => {
+            float_image(width, height, pixels, color_space).save_with_format(path, ImageFormat::Hdr)
+        }
+        OutputFormat::Pfm => {
+            let pixels: Vec<Color> = pixels
+                .iter()
+                .map(|c| color_space.convert_primaries(*c))
+                .collect();
+            pfm::write(path, width, height, &pixels).map_err(Into::into)
+        }
+    };
+    result.map_err(|err| OutputError::new(path, format!("cannot write image: {err}")))
+}
+
+/// Tone maps a row-major buffer of linear Rec.709 radiance and encodes it in `color_space`
+/// as an 8-bit image.
+pub fn display_image(
+    width: u32,
+    height: u32,
+    pixels: &[Color],
+    tone_mapping: &ToneMapping,
+    color_space: ColorSpace,
+) -> RgbImage {
+    assert_eq!(pixels.len(), (width * height) as usize);
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let color = tone_mapping.apply(pixels[(y * width + x) as usize]);
+        color_space.encode(color).to_rgb()
+    })
+}
+
+/// Writes the number of samples each pixel took as a black-red-yellow-white ramp, with
+/// `max_samples` mapped to white. The format is inferred from the extension.
+pub fn save_heatmap(
+    path: &Path,
+    width: u32,
+    height: u32,
+    samples: &[u32],
+    max_samples: u32,
+) -> Result<(), OutputError> {
+    assert_eq!(samples.len(), (width * height) as usize);
+    let OutputFormat::Ldr(format) = OutputFormat::from_path(path)? else {
+        return Err(OutputError::new(
+            path,
+            "heatmaps need an 8-bit image format",
+        ));
+    };
+    create_parent_dir(path)?;
+
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let t = samples[(y * width + x) as usize] as f64 / max_samples.max(1) as f64;
+        Color::new(3.0 * t, 3.0 * t - 1.0, 3.0 * t - 2.0).to_rgb()
+    })
+    .save_with_format(path, format)
+    .map_err(|err| OutputError::new(path, format!("cannot write heatmap: {err}")))
+}
+
+fn create_parent_dir(path: &Path) -> Result<(), OutputError> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).map_err(|err| {
+            OutputError::new(path, format!("cannot create output directory: {err}"))
+        })?;
+    }
+    Ok(())
+}
+
+fn float_image(
+    width: u32,
+    height: u32,
+    pixels: &[Color],
+    color_space: ColorSpace,
+) -> ImageBuffer<Rgb<f32>, Vec<f32>> {
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let color = color_space.convert_primaries(pixels[(y * width + x) as usize]);
+        Rgb([color.x as f32, color.y as f32, color.z as f32])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use image::ImageFormat;
+
+    use super::OutputFormat;
+
+    #[test]
+    fn format_is_inferred_from_extension() {
+        let format = |path: &str| OutputFormat::from_path(Path::new(path)).ok();
+        assert_eq!(format("a/b.png"), Some(OutputFormat::Ldr(ImageFormat::Png)));
+        assert_eq!(format("b.JPG"), Some(OutputFormat::Ldr(ImageFormat::Jpeg)));
+        assert_eq!(format("b.tiff"), Some(OutputFormat::Ldr(ImageFormat::Tiff)));
+        assert_eq!(format("b.exr"), Some(OutputFormat::Exr));
+        assert_eq!(format("b.hdr"), Some(OutputFormat::Hdr));
+        assert_eq!(format("b.pfm"), Some(OutputFormat::Pfm));
+        assert_eq!(format("b.xyz"), None);
+        assert_eq!(format("b.gif"), None);
+        assert_eq!(format("b.webp"), None);
+        assert_eq!(format("b"), None);
+    }
+}
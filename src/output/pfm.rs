@@ -0,0 +1,22 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::vec3::Color;
+
+/// Writes a colour PFM: a text header followed by little-endian `f32` triples, bottom row first.
+pub fn write(path: &Path, width: u32, height: u32, pixels: &[Color]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    // a negative scale marks the data as little-endian
+    write!(writer, "PF\n{width} {height}\n-1.0\n")?;
+    for row in pixels.chunks(width as usize).rev() {
+        for color in row {
+            for channel in [color.x, color.y, color.z] {
+                writer.write_all(&(channel as f32).to_le_bytes())?;
+            }
+        }
+    }
+    writer.flush()
+}
@@ -0,0 +1,103 @@
+use crate::vec3::Color;
+
+/// Curve compressing scene radiance into the displayable [0, 1] range.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ToneMapper {
+    /// Values above 1 are clipped
+    Clamp,
+    /// `L / (1 + L)` on luminance, preserving hue
+    Reinhard,
+    /// Reinhard with luminance `white_point` mapped to exactly 1
+    ReinhardExtended { white_point: f64 },
+    /// Narkowicz's fit of the ACES filmic reference curve
+    Aces,
+    /// John Hable's filmic curve from Uncharted 2
+    Hable,
+}
+
+/// Exposure and tone curve applied to linear radiance before 8-bit encoding.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ToneMapping {
+    pub mapper: ToneMapper,
+    /// Exposure compensation in stops; every step doubles the brightness
+    pub exposure: f64,
+}
+
+impl Default for ToneMapping {
+    fn default() -> Self {
+        Self {
+            mapper: ToneMapper::Clamp,
+            exposure: 0.0,
+        }
+    }
+}
+
+impl ToneMapping {
+    pub fn apply(&self, color: Color) -> Color {
+        let color = color * self.exposure.exp2();
+        match self.mapper {
+            ToneMapper::Clamp => color,
+            ToneMapper::Reinhard => scale_luminance(color, |l| l / (1.0 + l)),
+            ToneMapper::ReinhardExtended { white_point } => scale_luminance(color, |l| {
+                l * (1.0 + l / (white_point * white_point)) / (1.0 + l)
+            }),
+            ToneMapper::Aces => map_channels(color, aces),
+            ToneMapper::Hable => {
+                const EXPOSURE_BIAS: f64 = 2.0;
+                const WHITE_POINT: f64 = 11.2;
+                let white_scale = 1.0 / hable_partial(WHITE_POINT);
+                map_channels(color, |x| hable_partial(x * EXPOSURE_BIAS) * white_scale)
+            }
+        }
+    }
+}
+
+fn scale_luminance(color: Color, curve: impl Fn(f64) -> f64) -> Color {
+    let l = color.luminance();
+    if l <= 0.0 {
+        return Color::BLACK;
+    }
+    color * (curve(l) / l)
+}
+
+fn map_channels(color: Color, curve: impl Fn(f64) -> f64) -> Color {
+    Color::new(curve(color.x), curve(color.y), curve(color.z))
+}
+
+fn aces(x: f64) -> f64 {
+    let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+    ((x * (a * x + b)) / (x * (c * x + d) + e)).clamp(0.0, 1.0)
+}
+
+fn hable_partial(x: f64) -> f64 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vec3::Color;
+
+    use super::{ToneMapper, ToneMapping};
+
+    fn map(mapper: ToneMapper, value: f64) -> Color {
+        ToneMapping {
+            mapper,
+            exposure: 0.0,
+        }
+        .apply(Color::all(value))
+    }
+
+    #[test]
+    fn curves_compress_into_unit_range() {
+        let extended = ToneMapper::ReinhardExtended { white_point: 4.0 };
+        assert!((map(extended, 4.0).x - 1.0).abs() < 1e-9);
+        assert!((map(ToneMapper::Reinhard, 1.0).x - 0.5).abs() < 1e-9);
+        assert!(map(ToneMapper::Reinhard, 1000.0).x < 1.0);
+        assert!(map(ToneMapper::Aces, 1000.0).x <= 1.0);
+        for mapper in [ToneMapper::Reinhard, ToneMapper::Aces, ToneMapper::Hable] {
+            assert_eq!(map(mapper, 0.0).x, 0.0);
+            assert!(map(mapper, 0.5).x < map(mapper, 1.0).x);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::vec3::{Point, Vec3};
 
+#[derive(Clone, Copy)]
 pub struct Ray {
     pub origin: Point,
     pub dir: Vec3,
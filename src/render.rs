@@ -0,0 +1,312 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use image::RgbImage;
+use rayon::prelude::*;
+
+use crate::{
+    camera::Camera,
+    color::ColorSpace,
+    film::Film,
+    filter::Filter,
+    object::{Hittable, HittableList},
+    output::{self, ToneMapping},
+    vec3::Color,
+};
+
+/// Image rows rendered together into one film tile.
+const TILE_ROWS: u32 = 8;
+
+/// How the samples of a render are turned into the final image.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderOptions {
+    pub filter: Filter,
+    pub tone_mapping: ToneMapping,
+    pub color_space: ColorSpace,
+}
+
+/// Renders `world` as seen by `camera` and returns the tone mapped 8-bit image. `lights` are
+/// the emitters sampled directly by the MIS integrator and may be empty.
+pub fn render<T: Hittable + Sync>(
+    camera: &Camera,
+    world: &T,
+    lights: &HittableList,
+    options: &RenderOptions,
+) -> RgbImage {
+    let framebuffer = Renderer::new(camera, world, lights)
+        .with_filter(options.filter)
+        .render();
+    output::display_image(
+        framebuffer.width,
+        framebuffer.height,
+        &framebuffer.pixels,
+        &options.tone_mapping,
+        options.color_space,
+    )
+}
+
+/// How far a render has come, reported after every finished image row.
+#[derive(Clone, Copy, Debug)]
+pub struct Progress {
+    pub pixels_done: u32,
+    pub total_pixels: u32,
+    pub elapsed: Duration,
+    /// Remaining time extrapolated from the pace so far
+    pub eta: Duration,
+}
+
+/// Stops a render from another thread. Clones share the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Linear Rec.709 radiance of a render, in row-major order.
+pub struct Framebuffer {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Color>,
+    /// Samples taken through each pixel
+    pub samples: Vec<u32>,
+    /// Whether the render was cancelled; pixels it did not reach are black
+    pub cancelled: bool,
+}
+
+type ProgressCallback<'a> = Box<dyn Fn(Progress) + Send + Sync + 'a>;
+
+/// Renders a world through a camera on the rayon thread pool.
+pub struct Renderer<'a, T> {
+    camera: &'a Camera,
+    world: &'a T,
+    lights: &'a HittableList,
+    filter: Filter,
+    progress: Option<ProgressCallback<'a>>,
+    cancellation: CancellationToken,
+}
+
+impl<'a, T: Hittable + Sync> Renderer<'a, T> {
+    /// `lights` are the emitters sampled directly by the MIS integrator and may be empty.
+    pub fn new(camera: &'a Camera, world: &'a T, lights: &'a HittableList) -> Self {
+        Self {
+            camera,
+            world,
+            lights,
+            filter: Filter::default(),
+            progress: None,
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Calls `callback` from the render threads whenever an image row is finished.
+    pub fn with_progress(mut self, callback: impl Fn(Progress) + Send + Sync + 'a) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Stops rendering once `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// An empty film of the camera's image size with this renderer's filter.
+    pub fn film(&self) -> Film {
+        let (width, height) = self.camera.image_size();
+        Film::new(width, height, self.filter)
+    }
+
+    /// Renders every pixel with the camera's fixed or adaptive sample count.
+    pub fn render(&self) -> Framebuffer {
+        let mut film = self.film();
+        let cancelled = self.render_rows(&mut film, |tile, x, y| {
+            self.camera
+                .render_pixel(x, y, self.world, self.lights, tile);
+        });
+        let (width, height) = self.camera.image_size();
+        Framebuffer {
+            width,
+            height,
+            pixels: film.resolve(),
+            samples: film.samples().to_vec(),
+            cancelled,
+        }
+    }
+
+    /// Brings every pixel of `film` up to `target_samples`, continuing each pixel's random
+    /// sequence where it stopped. Pixels that already have that many are left alone, so
+    /// repeating a pass that was cut short finishes it instead of oversampling the pixels it
+    /// reached. Returns false if the pass was cancelled, in which case the pixels it did not
+    /// reach keep the samples they had.
+    pub fn render_pass(&self, film: &mut Film, target_samples: u32) -> bool {
+        let first_samples = film.samples().to_vec();
+        let width = self.camera.image_size().0;
+        let cancelled = self.render_rows(film, |tile, x, y| {
+            let first_sample = first_samples[(y * width + x) as usize];
+            let samples = target_samples.saturating_sub(first_sample);
+            self.camera
+                .render_samples(x, y, self.world, self.lights, first_sample, samples, tile);
+        });
+        !cancelled
+    }
+
+    /// Renders bands of rows in parallel, each into its own tile of `film`, then merges the
+    /// tiles in row order so the filtered sums do not depend on thread scheduling. Returns
+    /// whether the render was cancelled.
+    fn render_rows(
+        &self,
+        film: &mut Film,
+        render_pixel: impl Fn(&mut Film, u32, u32) + Sync,
+    ) -> bool {
+        let (width, height) = self.camera.image_size();
+        let start = Instant::now();
+        let pixels_done = AtomicU32::new(0);
+        let total_pixels = width * height;
+
+        let tiles: Vec<Film> = (0..height.div_ceil(TILE_ROWS))
+            .into_par_iter()
+            .map(|band| {
+                let rows = band * TILE_ROWS..((band + 1) * TILE_ROWS).min(height);
+                let mut tile = film.tile(rows.clone());
+                for y in rows {
+                    for x in 0..width {
+                        if self.cancellation.is_cancelled() {
+                            return tile;
+                        }
+                        render_pixel(&mut tile, x, y);
+                    }
+                    let done = pixels_done.fetch_add(width, Ordering::Relaxed) + width;
+                    if let Some(callback) = &self.progress {
+                        let elapsed = start.elapsed();
+                        let remaining = (total_pixels - done) as f64 / done as f64;
+                        callback(Progress {
+                            pixels_done: done,
+                            total_pixels,
+                            elapsed,
+                            eta: elapsed.mul_f64(remaining),
+                        });
+                    }
+                }
+                tile
+            })
+            .collect();
+        for tile in &tiles {
+            film.merge(tile);
+        }
+        self.cancellation.is_cancelled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use crate::{
+        camera::{Camera, CameraOption, Integrator, OutputQuality},
+        material::Lambertian,
+        object::{HittableList, Sphere},
+        sampler::SamplerKind,
+        shutter::Shutter,
+        vec3::{Color, Point, Vec3},
+    };
+
+    use super::{CancellationToken, Renderer};
+
+    fn camera() -> Camera {
+        Camera::new(CameraOption {
+            vfov: 90.0,
+            look_from: Point::new(0.0, 0.0, 0.0),
+            look_at: Point::new(0.0, 0.0, -1.0),
+            vup: Vec3::new(0.0, 1.0, 0.0),
+            defocus_angle: 0.0,
+            focus_distance: 1.0,
+            quality: OutputQuality {
+                image_width: 8,
+                image_height: 20,
+                samples_per_pixel: 2,
+                max_depth: 4,
+                integrator: Integrator::Path,
+                sampler: SamplerKind::Random,
+                adaptive: None,
+                seed: 0,
+            },
+            bg_color: Color::new(0.5, 0.7, 1.0),
+            shutter: Shutter::default(),
+        })
+    }
+
+    #[test]
+    fn reports_progress_and_stops_when_cancelled() {
+        let camera = camera();
+        let mut world = HittableList::empty();
+        let material = Arc::new(Lambertian::new_solid_color(Color::new(0.5, 0.5, 0.5)));
+        world.add(Sphere::stationary(
+            Point::new(0.0, 0.0, -1.0),
+            0.5,
+            material,
+        ));
+        let lights = HittableList::empty();
+
+        let last_progress = AtomicU32::new(0);
+        let framebuffer = Renderer::new(&camera, &world, &lights)
+            .with_progress(|progress| {
+                last_progress.fetch_max(progress.pixels_done, Ordering::Relaxed);
+            })
+            .render();
+        assert!(!framebuffer.cancelled);
+        assert_eq!(last_progress.load(Ordering::Relaxed), 8 * 20);
+        assert!(framebuffer.samples.iter().all(|&n| n == 2));
+        assert!(framebuffer.pixels.iter().all(|c| c.luminance() > 0.0));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let framebuffer = Renderer::new(&camera, &world, &lights)
+            .with_cancellation(token)
+            .render();
+        assert!(framebuffer.cancelled);
+        assert!(framebuffer.samples.iter().all(|&n| n == 0));
+    }
+
+    #[test]
+    fn repeating_a_cancelled_pass_fills_pixels_to_its_target() {
+        let camera = camera();
+        let world = HittableList::empty();
+        let lights = HittableList::empty();
+
+        let token = CancellationToken::new();
+        let renderer = Renderer::new(&camera, &world, &lights)
+            .with_cancellation(token.clone())
+            .with_progress(|_| token.cancel());
+        let mut film = renderer.film();
+        assert!(!renderer.render_pass(&mut film, 3));
+        assert!(film.samples().contains(&3) && film.min_samples() == 0);
+
+        let renderer = Renderer::new(&camera, &world, &lights);
+        assert!(renderer.render_pass(&mut film, 3));
+        assert!(film.samples().iter().all(|&n| n == 3));
+    }
+}
@@ -0,0 +1,91 @@
+use crate::utils::rand_double;
+
+use super::{permute, PixelSeed, Sampler};
+
+const DIMENSIONS: usize = 64;
+/// One prime base per dimension; later dimensions fall back to random samples.
+const PRIMES: [u32; DIMENSIONS] = first_primes();
+
+/// Digits with a weight below this no longer change an `f64` sample meaningfully.
+const MIN_DIGIT_WEIGHT: f64 = 1e-12;
+
+const fn first_primes<const N: usize>() -> [u32; N] {
+    let mut primes = [0; N];
+    let mut count = 0;
+    let mut n = 2;
+    while count < N {
+        let mut divisor = 2;
+        let mut is_prime = true;
+        while divisor * divisor <= n {
+            if n % divisor == 0 {
+                is_prime = false;
+                break;
+            }
+            divisor += 1;
+        }
+        if is_prime {
+            primes[count] = n;
+            count += 1;
+        }
+        n += 1;
+    }
+    primes
+}
+
+/// The Halton sequence over a pixel's samples, randomised per pixel and dimension by
+/// permuting the digits of the radical inverse.
+pub struct HaltonSampler {
+    pixel: PixelSeed,
+    index: u32,
+    dimension: u32,
+}
+
+impl HaltonSampler {
+    pub(super) fn new(pixel: PixelSeed, index: u32) -> Self {
+        Self {
+            pixel,
+            index,
+            dimension: 0,
+        }
+    }
+
+    fn scrambled_radical_inverse(&self, dimension: u32) -> f64 {
+        let Some(&base) = PRIMES.get(dimension as usize) else {
+            return rand_double();
+        };
+        let seed = self.pixel.for_dimension(dimension);
+        let inv_base = 1.0 / base as f64;
+
+        let mut index = self.index;
+        let mut weight = inv_base;
+        let mut value = 0.0;
+        let mut digit_position = 0u64;
+        // keep going past the last non-zero digit, since permuted zeros are usually not zero
+        while weight > MIN_DIGIT_WEIGHT {
+            let digit = index % base;
+            let digit_seed = (seed ^ digit_position.wrapping_mul(0x9e3779b97f4a7c15)) as u32;
+            value += permute(digit, base, digit_seed) as f64 * weight;
+            index /= base;
+            weight *= inv_base;
+            digit_position += 1;
+        }
+        value.min(1.0 - f64::EPSILON)
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn get_1d(&mut self) -> f64 {
+        let value = self.scrambled_radical_inverse(self.dimension);
+        self.dimension += 1;
+        value
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let value = (
+            self.scrambled_radical_inverse(self.dimension),
+            self.scrambled_radical_inverse(self.dimension + 1),
+        );
+        self.dimension += 2;
+        value
+    }
+}
@@ -0,0 +1,177 @@
+mod halton;
+mod random;
+mod sobol;
+mod stratified;
+mod warp;
+
+use crate::utils::seeded_hash;
+
+pub use halton::HaltonSampler;
+pub use random::RandomSampler;
+pub use sobol::SobolSampler;
+pub use stratified::StratifiedSampler;
+pub use warp::{sample_cosine_hemisphere, sample_uniform_disk, sample_uniform_sphere};
+
+/// Source of the sample values for one camera sample. Every call consumes the next dimension,
+/// so the camera and each bounce of the integrator always read the same dimensions in the
+/// same order, and the sampler can make each dimension well distributed across a pixel's
+/// samples and decorrelated from the others. Volumes are the exception: each one a ray is
+/// tested against draws a dimension, so paths through media shift the later dimensions.
+pub trait Sampler {
+    fn get_1d(&mut self) -> f64;
+    fn get_2d(&mut self) -> (f64, f64);
+}
+
+/// Which `Sampler` generates camera samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplerKind {
+    /// Independent uniform random numbers
+    Random,
+    /// Jittered strata, shuffled per dimension
+    Stratified,
+    /// Halton sequence with random digit permutations
+    Halton,
+    /// Sobol sequence with Owen scrambling, padded across dimensions by shuffling
+    Sobol,
+}
+
+impl SamplerKind {
+    /// Creates the sampler for sample `index` of pixel (`x`, `y`) out of `samples_per_pixel`,
+    /// scrambled by `seed`.
+    pub fn create(
+        self,
+        seed: u64,
+        x: u32,
+        y: u32,
+        index: u32,
+        samples_per_pixel: u32,
+    ) -> Box<dyn Sampler> {
+        let pixel = PixelSeed::new(seed, x, y);
+        match self {
+            SamplerKind::Random => Box::new(RandomSampler),
+            SamplerKind::Stratified => {
+                Box::new(StratifiedSampler::new(pixel, index, samples_per_pixel))
+            }
+            SamplerKind::Halton => Box::new(HaltonSampler::new(pixel, index)),
+            SamplerKind::Sobol => Box::new(SobolSampler::new(pixel, index)),
+        }
+    }
+}
+
+/// Per-pixel hash from which the scrambling of every dimension is derived, so that
+/// neighbouring pixels do not share sample patterns.
+#[derive(Clone, Copy)]
+struct PixelSeed(u64);
+
+impl PixelSeed {
+    fn new(seed: u64, x: u32, y: u32) -> Self {
+        Self(seeded_hash(seed, &[x as u64, y as u64]))
+    }
+
+    fn for_dimension(self, dimension: u32) -> u64 {
+        seeded_hash(self.0, &[dimension as u64])
+    }
+}
+
+/// Converts 32 random bits to a float in [0, 1).
+fn bits_to_unit(bits: u32) -> f64 {
+    bits as f64 / (1u64 << 32) as f64
+}
+
+/// Kensler's hash-based permutation of `i` within `0..len`, from "Correlated Multi-Jittered
+/// Sampling".
+fn permute(mut i: u32, len: u32, seed: u32) -> u32 {
+    let mut w = len - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < len {
+            break;
+        }
+    }
+    (i.wrapping_add(seed)) % len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{permute, SamplerKind};
+
+    #[test]
+    fn permute_is_a_bijection() {
+        for len in [1, 2, 7, 16, 100] {
+            let mut seen = vec![false; len as usize];
+            for i in 0..len {
+                seen[permute(i, len, 0x1234_5678) as usize] = true;
+            }
+            assert!(seen.iter().all(|&s| s));
+        }
+    }
+
+    #[test]
+    fn low_discrepancy_samplers_stratify_the_first_dimension() {
+        const SAMPLES: u32 = 16;
+        for kind in [
+            SamplerKind::Stratified,
+            SamplerKind::Halton,
+            SamplerKind::Sobol,
+        ] {
+            let mut strata = [0; SAMPLES as usize];
+            for index in 0..SAMPLES {
+                let mut sampler = kind.create(0, 3, 5, index, SAMPLES);
+                let u = sampler.get_1d();
+                assert!((0.0..1.0).contains(&u));
+                strata[(u * SAMPLES as f64) as usize] += 1;
+            }
+            assert!(strata.iter().all(|&n| n == 1), "{kind:?}: {strata:?}");
+        }
+    }
+
+    #[test]
+    fn stratified_rounds_use_different_permutations() {
+        const SAMPLES: u32 = 16;
+        let strata = |round: u32| -> Vec<u32> {
+            (0..SAMPLES)
+                .map(|i| {
+                    let mut sampler =
+                        SamplerKind::Stratified.create(0, 3, 5, round * SAMPLES + i, SAMPLES);
+                    (sampler.get_1d() * SAMPLES as f64) as u32
+                })
+                .collect()
+        };
+        assert_ne!(strata(0), strata(1));
+    }
+
+    #[test]
+    fn stratified_2d_grid_fits_within_the_sample_count() {
+        // 10 samples: a 3x3 grid takes the first 9, the last one is random
+        const SAMPLES: u32 = 10;
+        let mut cells = [0; 9];
+        for index in 0..SAMPLES - 1 {
+            let mut sampler = SamplerKind::Stratified.create(0, 3, 5, index, SAMPLES);
+            let (u, v) = sampler.get_2d();
+            cells[(v * 3.0) as usize * 3 + (u * 3.0) as usize] += 1;
+        }
+        assert!(cells.iter().all(|&n| n == 1), "{cells:?}");
+    }
+}
@@ -0,0 +1,16 @@
+use crate::utils::rand_double;
+
+use super::Sampler;
+
+/// Independent uniform samples from the per-sample random stream.
+pub struct RandomSampler;
+
+impl Sampler for RandomSampler {
+    fn get_1d(&mut self) -> f64 {
+        rand_double()
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        (rand_double(), rand_double())
+    }
+}
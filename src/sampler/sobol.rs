@@ -0,0 +1,84 @@
+use super::{bits_to_unit, PixelSeed, Sampler};
+
+/// Generator matrix of the second Sobol dimension, one column per index bit; the first
+/// dimension is the van der Corput sequence, i.e. plain bit reversal.
+const SOBOL_SECOND_DIMENSION: [u32; 32] = second_dimension_matrix();
+
+const fn second_dimension_matrix() -> [u32; 32] {
+    let mut matrix = [0; 32];
+    let mut v = 1u32 << 31;
+    let mut bit = 0;
+    while bit < 32 {
+        matrix[bit] = v;
+        v ^= v >> 1;
+        bit += 1;
+    }
+    matrix
+}
+
+/// The 2D Sobol sequence with Owen scrambling, following Burley's "Practical Hash-based Owen
+/// Scrambling". Every 1D or 2D request reads the sequence at an index shuffled by its own
+/// seed, which decorrelates the dimensions while keeping each one stratified.
+pub struct SobolSampler {
+    pixel: PixelSeed,
+    index: u32,
+    dimension: u32,
+}
+
+impl SobolSampler {
+    pub(super) fn new(pixel: PixelSeed, index: u32) -> Self {
+        Self {
+            pixel,
+            index,
+            dimension: 0,
+        }
+    }
+
+    /// Shuffled index and scrambling seeds for the next dimension.
+    fn next_dimension(&mut self) -> (u32, u64) {
+        let seed = self.pixel.for_dimension(self.dimension);
+        self.dimension += 1;
+        (nested_uniform_scramble(self.index, seed as u32), seed)
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn get_1d(&mut self) -> f64 {
+        let (index, seed) = self.next_dimension();
+        bits_to_unit(nested_uniform_scramble(
+            index.reverse_bits(),
+            (seed >> 32) as u32,
+        ))
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let (index, seed) = self.next_dimension();
+        let mut y = 0;
+        for (bit, column) in SOBOL_SECOND_DIMENSION.iter().enumerate() {
+            if index & (1 << bit) != 0 {
+                y ^= column;
+            }
+        }
+        (
+            bits_to_unit(nested_uniform_scramble(
+                index.reverse_bits(),
+                (seed >> 32) as u32,
+            )),
+            bits_to_unit(nested_uniform_scramble(y, (seed >> 16) as u32 ^ 0x5bd1e995)),
+        )
+    }
+}
+
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    x
+}
+
+/// Owen scrambling of the bits of `x`, most significant bit first.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
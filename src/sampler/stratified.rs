@@ -0,0 +1,55 @@
+use crate::utils::{rand_double, seeded_hash};
+
+use super::{permute, PixelSeed, Sampler};
+
+/// Jittered stratification: sample `index` of every dimension falls in its own stratum, and
+/// strata are assigned to samples by an independent permutation per dimension. Samples past
+/// `samples_per_pixel` start a new, differently shuffled round. 2D dimensions use a square
+/// grid of `floor(sqrt(samples_per_pixel))²` cells; samples beyond it are uniformly random.
+pub struct StratifiedSampler {
+    pixel: PixelSeed,
+    index: u32,
+    samples_per_pixel: u32,
+    dimension: u32,
+}
+
+impl StratifiedSampler {
+    pub(super) fn new(pixel: PixelSeed, index: u32, samples_per_pixel: u32) -> Self {
+        Self {
+            pixel,
+            index,
+            samples_per_pixel: samples_per_pixel.max(1),
+            dimension: 0,
+        }
+    }
+
+    /// Stratum of this sample among `strata`, for the current dimension.
+    fn stratum(&mut self, strata: u32) -> u32 {
+        let round = self.index / self.samples_per_pixel;
+        let seed = seeded_hash(self.pixel.for_dimension(self.dimension), &[round as u64]);
+        self.dimension += 1;
+        permute(self.index % self.samples_per_pixel, strata, seed as u32)
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn get_1d(&mut self) -> f64 {
+        let strata = self.samples_per_pixel;
+        let stratum = self.stratum(strata);
+        (stratum as f64 + rand_double()) / strata as f64
+    }
+
+    fn get_2d(&mut self) -> (f64, f64) {
+        let side = (self.samples_per_pixel as f64).sqrt() as u32;
+        if self.index % self.samples_per_pixel >= side * side {
+            self.dimension += 2;
+            return (rand_double(), rand_double());
+        }
+        let stratum = self.stratum(side * side);
+        self.dimension += 1;
+        (
+            ((stratum % side) as f64 + rand_double()) / side as f64,
+            ((stratum / side) as f64 + rand_double()) / side as f64,
+        )
+    }
+}
@@ -0,0 +1,25 @@
+use std::f64::consts::PI;
+
+use crate::vec3::Vec3;
+
+/// Maps a 2D sample to a direction on the unit sphere with uniform density.
+pub fn sample_uniform_sphere((u1, u2): (f64, f64)) -> Vec3 {
+    let z = 1.0 - 2.0 * u1;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * u2;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// Maps a 2D sample to a direction around +z with density `cos(theta) / pi`.
+pub fn sample_cosine_hemisphere((u1, u2): (f64, f64)) -> Vec3 {
+    let phi = 2.0 * PI * u1;
+    let r = u2.sqrt();
+    Vec3::new(r * phi.cos(), r * phi.sin(), (1.0 - u2).max(0.0).sqrt())
+}
+
+/// Maps a 2D sample to a point in the unit disk in the xy plane with uniform density.
+pub fn sample_uniform_disk((u1, u2): (f64, f64)) -> Vec3 {
+    let r = u1.sqrt();
+    let theta = 2.0 * PI * u2;
+    Vec3::new(r * theta.cos(), r * theta.sin(), 0.0)
+}
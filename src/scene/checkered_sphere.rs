@@ -4,6 +4,7 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::Lambertian,
     object::{HittableList, Sphere},
+    shutter::Shutter,
     texture::CheckeredTexture,
     vec3::{Color, Point, Vec3},
 };
@@ -40,6 +41,7 @@ pub fn construct_checkered_sphere_scene(quality: OutputQuality) -> (HittableList
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
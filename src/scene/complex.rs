@@ -4,6 +4,7 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::{Dielectric, Lambertian, Material, Metal},
     object::{HittableList, Sphere},
+    shutter::Shutter,
     texture::CheckeredTexture,
     utils::{rand_double, rand_range},
     vec3::{Color, Point, Vec3},
@@ -98,6 +99,7 @@ pub fn construct_complex_scene(
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality: output_quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
@@ -4,6 +4,7 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::{DiffuseLight, Lambertian},
     object::{HittableList, Quad, Transformable},
+    shutter::Shutter,
     vec3::{Color, Point, Vec3},
 };
 
@@ -81,6 +82,7 @@ pub fn construct_cornell_box(quality: OutputQuality) -> (HittableList, Camera) {
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
@@ -4,6 +4,7 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::Lambertian,
     object::{HittableList, Sphere},
+    shutter::Shutter,
     texture::ImageTexture,
     vec3::{Color, Point, Vec3},
 };
@@ -43,6 +44,7 @@ pub fn construct_earth_scene(quality: OutputQuality) -> (HittableList, Camera) {
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
@@ -0,0 +1,541 @@
+use std::{collections::HashMap, ops::Range, path::Path, sync::Arc};
+
+use toml::Spanned;
+
+use crate::{
+    camera::{Camera, CameraOption, OutputQuality},
+    color::TransferFunction,
+    material::{Dielectric, DiffuseLight, Lambertian, Material, Metal},
+    object::{ConstantMedium, Hittable, HittableList, ObjModel, Quad, Sphere, Transformable},
+    shutter::{Shutter, ShutterCurve},
+    texture::{CheckeredTexture, ImageTexture, NoiseTexture, SolidColorTexture, Texture},
+    vec3::{Color, Matrix4, Vec3},
+};
+
+use super::{
+    error::SceneFileError,
+    schema::{
+        CameraDef, MaterialDef, MaterialKind, ObjectDef, ObjectKind, SceneDef, ShutterCurveKind,
+        ShutterDef, TextureDef, TextureKind, TransformDef, Triple,
+    },
+};
+
+type SharedTexture = Arc<dyn Texture + Send + Sync>;
+type SharedMaterial = Arc<dyn Material + Send + Sync>;
+type SharedHittable = Arc<dyn Hittable + Send + Sync>;
+type LoadResult<T> = Result<T, SceneFileError>;
+
+pub struct SceneBuilder<'a> {
+    path: &'a Path,
+    source: &'a str,
+    base_dir: &'a Path,
+    textures: HashMap<String, SharedTexture>,
+    materials: HashMap<String, SharedMaterial>,
+}
+
+fn vec3(t: &Triple) -> Vec3 {
+    Vec3::new(t[0], t[1], t[2])
+}
+
+fn span_of<T>(field: &Option<Spanned<T>>) -> Option<Range<usize>> {
+    field.as_ref().map(|f| f.span())
+}
+
+impl<'a> SceneBuilder<'a> {
+    pub fn new(path: &'a Path, source: &'a str, base_dir: &'a Path) -> Self {
+        Self {
+            path,
+            source,
+            base_dir,
+            textures: HashMap::new(),
+            materials: HashMap::new(),
+        }
+    }
+
+    pub fn build(
+        mut self,
+        scene: &SceneDef,
+        quality: OutputQuality,
+    ) -> LoadResult<(HittableList, Camera)> {
+        for (name, def) in &scene.textures {
+            let texture = self.build_texture(def)?;
+            self.textures.insert(name.clone(), texture);
+        }
+
+        for (name, def) in &scene.materials {
+            let material = self.build_material(def)?;
+            self.materials.insert(name.clone(), material);
+        }
+
+        if scene.objects.is_empty() {
+            return Err(SceneFileError::new(
+                self.path,
+                "scene must contain at least one object",
+            ));
+        }
+
+        let mut world = HittableList::empty();
+        for def in &scene.objects {
+            world.add_shared(self.build_object(def)?);
+        }
+
+        let camera = self.build_camera(&scene.camera, quality)?;
+        Ok((world, camera))
+    }
+
+    fn error<T>(&self, span: Range<usize>, message: impl Into<String>) -> LoadResult<T> {
+        Err(SceneFileError::at(self.path, self.source, span, message))
+    }
+
+    fn require<'f, T>(
+        &self,
+        field: &'f Option<Spanned<T>>,
+        name: &str,
+        kind: &str,
+        owner: Range<usize>,
+    ) -> LoadResult<&'f Spanned<T>> {
+        match field {
+            Some(value) => Ok(value),
+            None => self.error(owner, format!("`{kind}` requires field `{name}`")),
+        }
+    }
+
+    fn reject_unused<const N: usize>(
+        &self,
+        kind: &str,
+        fields: [(&str, Option<Range<usize>>); N],
+    ) -> LoadResult<()> {
+        for (name, span) in fields {
+            if let Some(span) = span {
+                return self.error(span, format!("field `{name}` is not used by `{kind}`"));
+            }
+        }
+        Ok(())
+    }
+
+    fn positive(&self, value: &Spanned<f64>, name: &str) -> LoadResult<f64> {
+        if *value.get_ref() <= 0.0 {
+            return self.error(value.span(), format!("`{name}` must be positive"));
+        }
+        Ok(*value.get_ref())
+    }
+
+    fn build_camera(&self, def: &Spanned<CameraDef>, quality: OutputQuality) -> LoadResult<Camera> {
+        let cam = def.get_ref();
+        if !(cam.vfov > 0.0 && cam.vfov < 180.0) {
+            return self.error(def.span(), "`vfov` must be between 0 and 180 degrees");
+        }
+        if vec3(&cam.look_from).dist(vec3(&cam.look_at)) == 0.0 {
+            return self.error(def.span(), "`look_from` and `look_at` must differ");
+        }
+        let shutter = match &cam.shutter {
+            Some(shutter) => self.build_shutter(shutter)?,
+            None => Shutter::default(),
+        };
+
+        Ok(Camera::new(CameraOption {
+            vfov: cam.vfov,
+            look_from: vec3(&cam.look_from),
+            look_at: vec3(&cam.look_at),
+            vup: vec3(&cam.vup),
+            defocus_angle: cam.defocus_angle,
+            focus_distance: cam.focus_distance,
+            bg_color: vec3(&cam.bg_color),
+            quality,
+            shutter,
+        }))
+    }
+
+    fn build_shutter(&self, def: &Spanned<ShutterDef>) -> LoadResult<Shutter> {
+        let shutter = def.get_ref();
+        if shutter.close < shutter.open {
+            return self.error(def.span(), "shutter `close` must not be before `open`");
+        }
+        if shutter.rolling_readout < 0.0 {
+            return self.error(def.span(), "`rolling_readout` must not be negative");
+        }
+        if shutter.open < 0.0 || shutter.close + shutter.rolling_readout > 1.0 {
+            return self.error(
+                def.span(),
+                "the shutter must open and close within the motion time range 0 to 1, \
+                 counting `rolling_readout`",
+            );
+        }
+
+        let kind = shutter
+            .curve
+            .as_ref()
+            .map_or(ShutterCurveKind::Box, |kind| *kind.get_ref());
+        if !matches!(kind, ShutterCurveKind::Custom) {
+            self.reject_unused(kind.as_str(), [("weights", span_of(&shutter.weights))])?;
+        }
+        let curve = match kind {
+            ShutterCurveKind::Box => ShutterCurve::Box,
+            ShutterCurveKind::Triangle => ShutterCurve::Triangle,
+            ShutterCurveKind::Custom => {
+                let weights = self.require(&shutter.weights, "weights", "custom", def.span())?;
+                match ShutterCurve::custom(weights.get_ref().clone()) {
+                    Ok(curve) => curve,
+                    Err(message) => return self.error(weights.span(), message),
+                }
+            }
+        };
+
+        Ok(Shutter {
+            open: shutter.open,
+            close: shutter.close,
+            curve,
+            rolling_readout: shutter.rolling_readout,
+        })
+    }
+
+    fn texture(&self, name: &Spanned<String>) -> LoadResult<SharedTexture> {
+        match self.textures.get(name.get_ref()) {
+            Some(texture) => Ok(texture.clone()),
+            None => self.error(name.span(), format!("unknown texture `{}`", name.get_ref())),
+        }
+    }
+
+    fn material(&self, name: &Spanned<String>) -> LoadResult<SharedMaterial> {
+        match self.materials.get(name.get_ref()) {
+            Some(material) => Ok(material.clone()),
+            None => self.error(
+                name.span(),
+                format!("unknown material `{}`", name.get_ref()),
+            ),
+        }
+    }
+
+    fn build_texture(&self, def: &Spanned<TextureDef>) -> LoadResult<SharedTexture> {
+        let span = def.span();
+        let tex = def.get_ref();
+        let kind = tex.kind.get_ref().as_str();
+
+        let texture: SharedTexture = match tex.kind.get_ref() {
+            TextureKind::Solid => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("scale", span_of(&tex.scale)),
+                        ("even", span_of(&tex.even)),
+                        ("odd", span_of(&tex.odd)),
+                        ("path", span_of(&tex.path)),
+                        ("srgb", span_of(&tex.srgb)),
+                    ],
+                )?;
+                let color = self.require(&tex.color, "color", kind, span)?;
+                Arc::new(SolidColorTexture::new(vec3(color.get_ref())))
+            }
+            TextureKind::Checkered => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("color", span_of(&tex.color)),
+                        ("path", span_of(&tex.path)),
+                        ("srgb", span_of(&tex.srgb)),
+                    ],
+                )?;
+                let scale = self.require(&tex.scale, "scale", kind, span.clone())?;
+                let scale = self.positive(scale, "scale")?;
+                let even = self.require(&tex.even, "even", kind, span.clone())?;
+                let odd = self.require(&tex.odd, "odd", kind, span)?;
+                Arc::new(CheckeredTexture::new_from_colors(
+                    scale,
+                    vec3(even.get_ref()),
+                    vec3(odd.get_ref()),
+                ))
+            }
+            TextureKind::Image => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("color", span_of(&tex.color)),
+                        ("scale", span_of(&tex.scale)),
+                        ("even", span_of(&tex.even)),
+                        ("odd", span_of(&tex.odd)),
+                    ],
+                )?;
+                let path = self.require(&tex.path, "path", kind, span)?;
+                let full_path = self.base_dir.join(path.get_ref());
+                let transfer = match tex.srgb.as_ref().map(|srgb| *srgb.get_ref()) {
+                    Some(false) => TransferFunction::Linear,
+                    _ => TransferFunction::Srgb,
+                };
+                match ImageTexture::load(&full_path, transfer) {
+                    Ok(texture) => Arc::new(texture),
+                    Err(err) => {
+                        return self.error(
+                            path.span(),
+                            format!("cannot load image `{}`: {err}", full_path.display()),
+                        )
+                    }
+                }
+            }
+            TextureKind::Noise => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("color", span_of(&tex.color)),
+                        ("even", span_of(&tex.even)),
+                        ("odd", span_of(&tex.odd)),
+                        ("path", span_of(&tex.path)),
+                        ("srgb", span_of(&tex.srgb)),
+                    ],
+                )?;
+                let scale = self.require(&tex.scale, "scale", kind, span)?;
+                let scale = self.positive(scale, "scale")?;
+                Arc::new(NoiseTexture::new_perlin(scale))
+            }
+        };
+        Ok(texture)
+    }
+
+    /// Resolves a material's base color, given either inline as a color field or as a texture reference.
+    fn color_or_texture(
+        &self,
+        color: &Option<Spanned<Triple>>,
+        color_name: &str,
+        texture: &Option<Spanned<String>>,
+        kind: &str,
+        owner: Range<usize>,
+    ) -> LoadResult<SharedTexture> {
+        match (color, texture) {
+            (Some(color), None) => Ok(Arc::new(SolidColorTexture::new(vec3(color.get_ref())))),
+            (None, Some(texture)) => self.texture(texture),
+            (Some(_), Some(texture)) => self.error(
+                texture.span(),
+                format!("`{kind}` accepts either `{color_name}` or `texture`, not both"),
+            ),
+            (None, None) => self.error(
+                owner,
+                format!("`{kind}` requires field `{color_name}` or `texture`"),
+            ),
+        }
+    }
+
+    fn build_material(&self, def: &Spanned<MaterialDef>) -> LoadResult<SharedMaterial> {
+        let span = def.span();
+        let mat = def.get_ref();
+        let kind = mat.kind.get_ref().as_str();
+
+        let material: SharedMaterial = match mat.kind.get_ref() {
+            MaterialKind::Lambertian => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("color", span_of(&mat.color)),
+                        ("fuzz", span_of(&mat.fuzz)),
+                        ("refraction_index", span_of(&mat.refraction_index)),
+                    ],
+                )?;
+                let texture =
+                    self.color_or_texture(&mat.albedo, "albedo", &mat.texture, kind, span)?;
+                Arc::new(Lambertian::new(texture))
+            }
+            MaterialKind::Metal => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("color", span_of(&mat.color)),
+                        ("texture", span_of(&mat.texture)),
+                        ("refraction_index", span_of(&mat.refraction_index)),
+                    ],
+                )?;
+                let albedo = self.require(&mat.albedo, "albedo", kind, span)?;
+                let fuzz = mat.fuzz.as_ref().map_or(0.0, |f| *f.get_ref());
+                Arc::new(Metal::new(vec3(albedo.get_ref()), fuzz))
+            }
+            MaterialKind::Dielectric => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("albedo", span_of(&mat.albedo)),
+                        ("color", span_of(&mat.color)),
+                        ("texture", span_of(&mat.texture)),
+                        ("fuzz", span_of(&mat.fuzz)),
+                    ],
+                )?;
+                let ri = self.require(&mat.refraction_index, "refraction_index", kind, span)?;
+                let ri = self.positive(ri, "refraction_index")?;
+                Arc::new(Dielectric::new(ri))
+            }
+            MaterialKind::DiffuseLight => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("albedo", span_of(&mat.albedo)),
+                        ("fuzz", span_of(&mat.fuzz)),
+                        ("refraction_index", span_of(&mat.refraction_index)),
+                    ],
+                )?;
+                let texture =
+                    self.color_or_texture(&mat.color, "color", &mat.texture, kind, span)?;
+                Arc::new(DiffuseLight::new(texture))
+            }
+        };
+        Ok(material)
+    }
+
+    fn build_object(&self, def: &Spanned<ObjectDef>) -> LoadResult<SharedHittable> {
+        let span = def.span();
+        let obj = def.get_ref();
+        let kind = obj.kind.get_ref().as_str();
+        let material = match &obj.material {
+            Some(name) => self.material(name)?,
+            // the boundary of a medium is never shaded
+            None if obj.medium.is_some() || matches!(obj.kind.get_ref(), ObjectKind::Obj) => {
+                Arc::new(Lambertian::new_solid_color(Color::all(0.73)))
+            }
+            None => return self.error(span, format!("`{kind}` requires field `material`")),
+        };
+
+        let mut object: SharedHittable = match obj.kind.get_ref() {
+            ObjectKind::Sphere => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("q", span_of(&obj.q)),
+                        ("u", span_of(&obj.u)),
+                        ("v", span_of(&obj.v)),
+                        ("a", span_of(&obj.a)),
+                        ("b", span_of(&obj.b)),
+                        ("path", span_of(&obj.path)),
+                    ],
+                )?;
+                let center = self.require(&obj.center, "center", kind, span.clone())?;
+                let center = vec3(center.get_ref());
+                let radius = self.require(&obj.radius, "radius", kind, span)?;
+                let radius = self.positive(radius, "radius")?;
+                match &obj.center_end {
+                    Some(end) => Arc::new(Sphere::moving(
+                        center,
+                        vec3(end.get_ref()),
+                        radius,
+                        material,
+                    )),
+                    None => Arc::new(Sphere::stationary(center, radius, material)),
+                }
+            }
+            ObjectKind::Quad => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("center", span_of(&obj.center)),
+                        ("center_end", span_of(&obj.center_end)),
+                        ("radius", span_of(&obj.radius)),
+                        ("a", span_of(&obj.a)),
+                        ("b", span_of(&obj.b)),
+                        ("path", span_of(&obj.path)),
+                    ],
+                )?;
+                let q = vec3(self.require(&obj.q, "q", kind, span.clone())?.get_ref());
+                let u = vec3(self.require(&obj.u, "u", kind, span.clone())?.get_ref());
+                let v = vec3(self.require(&obj.v, "v", kind, span.clone())?.get_ref());
+                if u.cross(&v).length_squared() == 0.0 {
+                    return self.error(span, "`u` and `v` of a quad must not be parallel");
+                }
+                Arc::new(Quad::new(q, u, v, material))
+            }
+            ObjectKind::Box => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("center", span_of(&obj.center)),
+                        ("center_end", span_of(&obj.center_end)),
+                        ("radius", span_of(&obj.radius)),
+                        ("q", span_of(&obj.q)),
+                        ("u", span_of(&obj.u)),
+                        ("v", span_of(&obj.v)),
+                        ("path", span_of(&obj.path)),
+                    ],
+                )?;
+                let a = vec3(self.require(&obj.a, "a", kind, span.clone())?.get_ref());
+                let b = vec3(self.require(&obj.b, "b", kind, span)?.get_ref());
+                Arc::new(HittableList::rectangular_box(&a, &b, material))
+            }
+            ObjectKind::Obj => {
+                self.reject_unused(
+                    kind,
+                    [
+                        ("center", span_of(&obj.center)),
+                        ("center_end", span_of(&obj.center_end)),
+                        ("radius", span_of(&obj.radius)),
+                        ("q", span_of(&obj.q)),
+                        ("u", span_of(&obj.u)),
+                        ("v", span_of(&obj.v)),
+                        ("a", span_of(&obj.a)),
+                        ("b", span_of(&obj.b)),
+                    ],
+                )?;
+                let path = self.require(&obj.path, "path", kind, span)?;
+                match ObjModel::load(&self.base_dir.join(path.get_ref()), material) {
+                    Ok(model) => Arc::new(model),
+                    Err(err) => return self.error(path.span(), format!("cannot load OBJ: {err}")),
+                }
+            }
+        };
+
+        for transform in &obj.transforms {
+            let TransformDef {
+                translate,
+                rotate_x,
+                rotate_y,
+                rotate_z,
+                scale,
+                matrix,
+            } = transform.get_ref();
+            let set = [
+                translate.is_some(),
+                rotate_x.is_some(),
+                rotate_y.is_some(),
+                rotate_z.is_some(),
+                scale.is_some(),
+                matrix.is_some(),
+            ];
+            if set.iter().filter(|&&set| set).count() != 1 {
+                return self.error(
+                    transform.span(),
+                    "a transform must set exactly one of `translate`, `rotate_x`, `rotate_y`, \
+                     `rotate_z`, `scale` or `matrix`",
+                );
+            }
+
+            object = if let Some(offset) = translate {
+                Arc::new(object.translate(vec3(offset)))
+            } else if let Some(angle) = rotate_y {
+                Arc::new(object.rotate_y(*angle))
+            } else {
+                let matrix = if let Some(angle) = rotate_x {
+                    Matrix4::rotation_x(*angle)
+                } else if let Some(angle) = rotate_z {
+                    Matrix4::rotation_z(*angle)
+                } else if let Some(factors) = scale {
+                    Matrix4::scaling(vec3(factors))
+                } else {
+                    Matrix4::new(matrix.expect("exactly one transform field is set"))
+                };
+                if !matrix.is_affine() {
+                    return self.error(
+                        transform.span(),
+                        "the last row of a transform matrix must be [0, 0, 0, 1]",
+                    );
+                }
+                if matrix.inverse().is_none() {
+                    return self.error(transform.span(), "transform is not invertible");
+                }
+                Arc::new(object.transform(matrix))
+            };
+        }
+
+        if let Some(medium) = &obj.medium {
+            let density = self.positive(&medium.get_ref().density, "density")?;
+            object = Arc::new(ConstantMedium::new(
+                object,
+                density,
+                vec3(&medium.get_ref().albedo),
+            ));
+        }
+
+        Ok(object)
+    }
+}
@@ -0,0 +1,64 @@
+use std::{
+    fmt::{self, Display},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// An error raised while reading a scene file, pointing at the offending location when known.
+#[derive(Debug)]
+pub struct SceneFileError {
+    path: PathBuf,
+    location: Option<(usize, usize)>,
+    message: String,
+}
+
+impl SceneFileError {
+    pub fn new(path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at(path: &Path, source: &str, span: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            location: Some(line_column(source, span.start)),
+            message: message.into(),
+        }
+    }
+
+    pub fn from_toml(path: &Path, source: &str, err: toml::de::Error) -> Self {
+        match err.span() {
+            Some(span) => Self::at(path, source, span, err.message()),
+            None => Self::new(path, err.message()),
+        }
+    }
+}
+
+impl Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(
+                f,
+                "{}:{}:{}: {}",
+                self.path.display(),
+                line,
+                column,
+                self.message
+            ),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
@@ -0,0 +1,134 @@
+mod builder;
+mod error;
+mod schema;
+
+use std::{fs, path::Path};
+
+use crate::{
+    camera::{Camera, OutputQuality},
+    object::HittableList,
+};
+
+use builder::SceneBuilder;
+pub use error::SceneFileError;
+use schema::SceneDef;
+
+/// Loads a TOML scene description. Relative texture paths are resolved against the scene file's directory.
+pub fn load_scene_file(
+    path: &Path,
+    quality: OutputQuality,
+) -> Result<(HittableList, Camera), SceneFileError> {
+    let source = fs::read_to_string(path)
+        .map_err(|err| SceneFileError::new(path, format!("cannot read scene file: {err}")))?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    parse_scene(&source, path, base_dir, quality)
+}
+
+pub fn parse_scene(
+    source: &str,
+    path: &Path,
+    base_dir: &Path,
+    quality: OutputQuality,
+) -> Result<(HittableList, Camera), SceneFileError> {
+    let scene: SceneDef =
+        toml::from_str(source).map_err(|err| SceneFileError::from_toml(path, source, err))?;
+    SceneBuilder::new(path, source, base_dir).build(&scene, quality)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camera::Integrator, object::Hittable, sampler::SamplerKind};
+
+    fn quality() -> OutputQuality {
+        OutputQuality {
+            image_width: 10,
+            image_height: 10,
+            samples_per_pixel: 1,
+            max_depth: 1,
+            integrator: Integrator::Mis,
+            sampler: SamplerKind::Random,
+            adaptive: None,
+            seed: 0,
+        }
+    }
+
+    fn parse(source: &str) -> Result<(HittableList, Camera), SceneFileError> {
+        parse_scene(source, Path::new("test.toml"), Path::new("."), quality())
+    }
+
+    const CAMERA: &str =
+        "[camera]\nvfov = 20.0\nlook_from = [0.0, 0.0, 5.0]\nlook_at = [0.0, 0.0, 0.0]\n";
+
+    #[test]
+    fn test_load_cornell_box_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenes/cornell_box.toml");
+        let (world, _) = load_scene_file(&path, quality()).expect("scene file should load");
+        assert_eq!(world.objects().len(), 8);
+        assert!(world.bounding_box().y.contains(554.0));
+    }
+
+    #[test]
+    fn test_unknown_material_reports_location() {
+        let source = format!(
+            "{CAMERA}\n[[objects]]\ntype = \"sphere\"\ncenter = [0.0, 0.0, 0.0]\nradius = 1.0\nmaterial = \"missing\"\n"
+        );
+        let err = parse(&source).err().expect("unknown material should fail");
+        assert_eq!(
+            err.to_string(),
+            "test.toml:10:12: unknown material `missing`"
+        );
+    }
+
+    #[test]
+    fn test_invalid_field_reports_location() {
+        let source =
+            format!("{CAMERA}\n[materials.grey]\ntype = \"lambertian\"\nalbedo = [0.5, 0.5]\n");
+        let err = parse(&source).err().expect("short albedo should fail");
+        assert!(err
+            .to_string()
+            .starts_with("test.toml:8:10: invalid length 2"));
+    }
+
+    #[test]
+    fn test_invalid_shutter_curve_reports_location() {
+        let source = format!(
+            "{CAMERA}shutter = {{ curve = \"custom\", weights = [0.0, 0.0] }}\n\n[[objects]]\ntype = \"sphere\"\ncenter = [0.0, 0.0, 0.0]\nradius = 1.0\nmaterial = \"grey\"\n\n[materials.grey]\ntype = \"lambertian\"\nalbedo = [0.5, 0.5, 0.5]\n"
+        );
+        let err = parse(&source)
+            .err()
+            .expect("a curve that never opens should fail");
+        assert_eq!(
+            err.to_string(),
+            "test.toml:5:41: a shutter curve must open at some point"
+        );
+    }
+
+    #[test]
+    fn test_shutter_outside_motion_range_is_rejected() {
+        let source = format!(
+            "{CAMERA}shutter = {{ open = 0.2, close = 0.8, rolling_readout = 0.5 }}\n\n[[objects]]\ntype = \"sphere\"\ncenter = [0.0, 0.0, 0.0]\nradius = 1.0\nmaterial = \"grey\"\n\n[materials.grey]\ntype = \"lambertian\"\nalbedo = [0.5, 0.5, 0.5]\n"
+        );
+        let err = parse(&source)
+            .err()
+            .expect("a rolling shutter closing after time 1 should fail");
+        assert!(err
+            .to_string()
+            .starts_with("test.toml:5:11: the shutter must open and close within"));
+    }
+
+    #[test]
+    fn test_medium_density_must_be_positive() {
+        let source = format!(
+            "{CAMERA}\n[[objects]]\ntype = \"box\"\na = [0.0, 0.0, 0.0]\nb = [1.0, 1.0, 1.0]\nmedium = {{ density = 0.0 }}\n"
+        );
+        let err = parse(&source).err().expect("an empty medium should fail");
+        assert_eq!(
+            err.to_string(),
+            "test.toml:10:22: `density` must be positive"
+        );
+
+        let (world, _) = parse(&source.replace("0.0 }", "0.5 }")).expect("a medium should load");
+        assert_eq!(world.objects().len(), 1);
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use toml::Spanned;
+
+pub type Triple = [f64; 3];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SceneDef {
+    pub camera: Spanned<CameraDef>,
+    #[serde(default)]
+    pub textures: BTreeMap<String, Spanned<TextureDef>>,
+    #[serde(default)]
+    pub materials: BTreeMap<String, Spanned<MaterialDef>>,
+    #[serde(default)]
+    pub objects: Vec<Spanned<ObjectDef>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraDef {
+    pub vfov: f64,
+    pub look_from: Triple,
+    pub look_at: Triple,
+    #[serde(default = "default_vup")]
+    pub vup: Triple,
+    #[serde(default)]
+    pub defocus_angle: f64,
+    #[serde(default = "default_focus_distance")]
+    pub focus_distance: f64,
+    #[serde(default)]
+    pub bg_color: Triple,
+    pub shutter: Option<Spanned<ShutterDef>>,
+}
+
+fn default_vup() -> Triple {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_focus_distance() -> f64 {
+    10.0
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutterCurveKind {
+    Box,
+    Triangle,
+    Custom,
+}
+
+impl ShutterCurveKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Box => "box",
+            Self::Triangle => "triangle",
+            Self::Custom => "custom",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShutterDef {
+    #[serde(default)]
+    pub open: f64,
+    #[serde(default = "default_shutter_close")]
+    pub close: f64,
+    pub curve: Option<Spanned<ShutterCurveKind>>,
+    /// Openness of a `custom` curve at evenly spaced times
+    pub weights: Option<Spanned<Vec<f64>>>,
+    #[serde(default)]
+    pub rolling_readout: f64,
+}
+
+fn default_shutter_close() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureKind {
+    Solid,
+    Checkered,
+    Image,
+    Noise,
+}
+
+impl TextureKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Solid => "solid",
+            Self::Checkered => "checkered",
+            Self::Image => "image",
+            Self::Noise => "noise",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TextureDef {
+    #[serde(rename = "type")]
+    pub kind: Spanned<TextureKind>,
+    pub color: Option<Spanned<Triple>>,
+    pub scale: Option<Spanned<f64>>,
+    pub even: Option<Spanned<Triple>>,
+    pub odd: Option<Spanned<Triple>>,
+    pub path: Option<Spanned<String>>,
+    /// Whether an image stores sRGB-encoded color (the default) rather than linear data
+    pub srgb: Option<Spanned<bool>>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MaterialKind {
+    Lambertian,
+    Metal,
+    Dielectric,
+    DiffuseLight,
+}
+
+impl MaterialKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lambertian => "lambertian",
+            Self::Metal => "metal",
+            Self::Dielectric => "dielectric",
+            Self::DiffuseLight => "diffuse_light",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MaterialDef {
+    #[serde(rename = "type")]
+    pub kind: Spanned<MaterialKind>,
+    pub albedo: Option<Spanned<Triple>>,
+    pub color: Option<Spanned<Triple>>,
+    pub texture: Option<Spanned<String>>,
+    pub fuzz: Option<Spanned<f64>>,
+    pub refraction_index: Option<Spanned<f64>>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    Sphere,
+    Quad,
+    Box,
+    Obj,
+}
+
+impl ObjectKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sphere => "sphere",
+            Self::Quad => "quad",
+            Self::Box => "box",
+            Self::Obj => "obj",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectDef {
+    #[serde(rename = "type")]
+    pub kind: Spanned<ObjectKind>,
+    pub material: Option<Spanned<String>>,
+    pub center: Option<Spanned<Triple>>,
+    pub center_end: Option<Spanned<Triple>>,
+    pub radius: Option<Spanned<f64>>,
+    pub q: Option<Spanned<Triple>>,
+    pub u: Option<Spanned<Triple>>,
+    pub v: Option<Spanned<Triple>>,
+    pub a: Option<Spanned<Triple>>,
+    pub b: Option<Spanned<Triple>>,
+    pub path: Option<Spanned<String>>,
+    #[serde(default)]
+    pub transforms: Vec<Spanned<TransformDef>>,
+    /// Fills the (closed, convex) object with smoke or fog instead of rendering its surface
+    pub medium: Option<Spanned<MediumDef>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MediumDef {
+    pub density: Spanned<f64>,
+    #[serde(default = "default_medium_albedo")]
+    pub albedo: Triple,
+}
+
+fn default_medium_albedo() -> Triple {
+    [1.0, 1.0, 1.0]
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransformDef {
+    pub translate: Option<Triple>,
+    pub rotate_x: Option<f64>,
+    pub rotate_y: Option<f64>,
+    pub rotate_z: Option<f64>,
+    pub scale: Option<Triple>,
+    /// Row-major affine matrix
+    pub matrix: Option<[[f64; 4]; 4]>,
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::{
+    camera::{Camera, CameraOption, OutputQuality},
+    material::{Lambertian, Metal},
+    object::{BVHTree, HittableList, Instance, Sphere},
+    shutter::Shutter,
+    utils::rand_range,
+    vec3::{Color, Matrix4, Point, Vec3},
+};
+
+const GRID_SIZE: i32 = 20;
+const SPACING: f64 = 3.0;
+
+/// A table made of a top and four legs, built once and shared by every instance.
+fn table_prototype() -> Arc<BVHTree> {
+    let wood = Arc::new(Lambertian::new_solid_color(Color::new(0.45, 0.3, 0.15)));
+    let mut table = HittableList::empty();
+    table.add(HittableList::rectangular_box(
+        &Point::new(-1.0, 1.0, -0.6),
+        &Point::new(1.0, 1.1, 0.6),
+        wood.clone(),
+    ));
+    for (x, z) in [(-0.9, -0.5), (0.8, -0.5), (-0.9, 0.4), (0.8, 0.4)] {
+        table.add(HittableList::rectangular_box(
+            &Point::new(x, 0.0, z),
+            &Point::new(x + 0.1, 1.0, z + 0.1),
+            wood.clone(),
+        ));
+    }
+    Arc::new(BVHTree::from_list(table.objects()))
+}
+
+/// A field of tables that all share one prototype, each with its own placement and some
+/// with their own material.
+pub fn construct_instances_scene(quality: OutputQuality) -> (HittableList, Camera) {
+    let mut world = HittableList::empty();
+
+    let ground = Arc::new(Lambertian::new_solid_color(Color::all(0.5)));
+    world.add(Sphere::stationary(
+        Point::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    ));
+
+    let prototype = table_prototype();
+    for i in -GRID_SIZE / 2..GRID_SIZE / 2 {
+        for j in -GRID_SIZE / 2..GRID_SIZE / 2 {
+            let position = Vec3::new(
+                i as f64 * SPACING + rand_range(-0.5..0.5),
+                0.0,
+                j as f64 * SPACING + rand_range(-0.5..0.5),
+            );
+            let size = rand_range(0.7..1.2);
+            let matrix = Matrix4::translation(position)
+                * Matrix4::rotation_y(rand_range(0.0..360.0))
+                * Matrix4::scaling(Vec3::all(size));
+            let instance = Instance::new(prototype.clone(), matrix);
+
+            let instance = match rand_range(0..6) {
+                0 => instance.with_material(Arc::new(Lambertian::new_solid_color(
+                    Color::rand() * Color::rand(),
+                ))),
+                1 => instance.with_material(Arc::new(Metal::new(
+                    Color::rand_range(0.5..1.0),
+                    rand_range(0.0..0.3),
+                ))),
+                _ => instance,
+            };
+            world.add(instance);
+        }
+    }
+
+    let camera = Camera::new(CameraOption {
+        bg_color: Color::new(0.7, 0.8, 1.0),
+        vfov: 35.0,
+        look_from: Point::new(26.0, 14.0, 30.0),
+        look_at: Point::new(0.0, 0.0, 0.0),
+        vup: Vec3::new(0.0, 1.0, 0.0),
+        defocus_angle: 0.0,
+        focus_distance: 10.0,
+        quality,
+        shutter: Shutter::default(),
+    });
+
+    (world, camera)
+}
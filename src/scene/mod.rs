@@ -1,17 +1,27 @@
 mod checkered_sphere;
 mod complex;
 mod cornell_box;
+mod cornell_smoke;
 mod earth;
+mod file;
+mod instances;
+mod motion_blur;
 mod perlin_spheres;
 mod quads;
 mod simple_light;
 mod transformation;
+mod triangles;
 
 pub use checkered_sphere::construct_checkered_sphere_scene;
 pub use complex::construct_complex_scene;
 pub use cornell_box::construct_cornell_box;
+pub use cornell_smoke::construct_cornell_smoke;
 pub use earth::construct_earth_scene;
+pub use file::load_scene_file;
+pub use instances::construct_instances_scene;
+pub use motion_blur::construct_motion_blur_scene;
 pub use perlin_spheres::construct_perlin_spheres;
 pub use quads::construct_quads_scene;
 pub use simple_light::construct_simple_light;
 pub use transformation::construct_transformation_debug_scene;
+pub use triangles::construct_triangles_scene;
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use crate::{
+    camera::{Camera, CameraOption, OutputQuality},
+    material::{Lambertian, Metal},
+    object::{HittableList, Keyframe, MotionTransform, Sphere, Transformable},
+    shutter::Shutter,
+    vec3::{Color, Matrix4, Point, Quaternion, Vec3},
+};
+
+/// Objects moving over the shutter interval: a spinning box, a box sliding while it tumbles,
+/// a sphere moving in a straight line, and a static box for reference.
+pub fn construct_motion_blur_scene(quality: OutputQuality) -> (HittableList, Camera) {
+    let mut world = HittableList::empty();
+
+    let ground = Arc::new(Lambertian::new_solid_color(Color::all(0.5)));
+    world.add(Sphere::stationary(
+        Point::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    ));
+
+    let red = Arc::new(Lambertian::new_solid_color(Color::RED));
+    let unit_box = |material| {
+        HittableList::rectangular_box(
+            &Point::new(-1.0, -1.0, -1.0),
+            &Point::new(1.0, 1.0, 1.0),
+            material,
+        )
+    };
+
+    world.add(
+        unit_box(red)
+            .transform_moving(
+                Matrix4::translation(Vec3::new(-4.0, 1.0, 0.0)),
+                Matrix4::translation(Vec3::new(-4.0, 1.0, 0.0)) * Matrix4::rotation_y(60.0),
+            )
+            .expect("a rotation about y is invertible throughout"),
+    );
+
+    let teal = Arc::new(Lambertian::new_solid_color(Color::TEAL));
+    world.add(
+        MotionTransform::new(
+            Keyframe::new(
+                Vec3::new(-0.5, 1.0, 0.0),
+                Quaternion::IDENTITY,
+                Vec3::all(0.8),
+            ),
+            Keyframe::new(
+                Vec3::new(1.5, 1.0, 0.0),
+                Quaternion::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), -90.0),
+                Vec3::all(0.8),
+            ),
+            unit_box(teal),
+        )
+        .expect("a uniform scale stays invertible"),
+    );
+
+    let metal = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.9), 0.1));
+    world.add(Sphere::moving(
+        Point::new(4.0, 1.0, 0.0),
+        Point::new(4.0, 2.0, 0.0),
+        1.0,
+        metal,
+    ));
+
+    let orange = Arc::new(Lambertian::new_solid_color(Color::ORANGE));
+    world.add(unit_box(orange).translate(Vec3::new(0.0, 1.0, -4.0)));
+
+    let camera = Camera::new(CameraOption {
+        bg_color: Color::new(0.7, 0.8, 1.0),
+        vfov: 35.0,
+        look_from: Point::new(0.0, 5.0, 16.0),
+        look_at: Point::new(0.0, 1.0, 0.0),
+        vup: Vec3::new(0.0, 1.0, 0.0),
+        defocus_angle: 0.0,
+        focus_distance: 10.0,
+        quality,
+        shutter: Shutter::default(),
+    });
+
+    (world, camera)
+}
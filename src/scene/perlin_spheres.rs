@@ -4,6 +4,7 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::Lambertian,
     object::{HittableList, Sphere},
+    shutter::Shutter,
     texture::NoiseTexture,
     vec3::{Color, Point, Vec3},
 };
@@ -34,6 +35,7 @@ pub fn construct_perlin_spheres(quality: OutputQuality) -> (HittableList, Camera
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
@@ -4,6 +4,7 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::Lambertian,
     object::{HittableList, Quad},
+    shutter::Shutter,
     vec3::{Color, Point, Vec3},
 };
 
@@ -60,6 +61,7 @@ pub fn construct_quads_scene(quality: OutputQuality) -> (HittableList, Camera) {
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
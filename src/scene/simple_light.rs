@@ -4,6 +4,7 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::{DiffuseLight, Lambertian},
     object::{HittableList, Quad, Sphere},
+    shutter::Shutter,
     texture::NoiseTexture,
     vec3::{Color, Point, Vec3},
 };
@@ -42,6 +43,7 @@ pub fn construct_simple_light(quality: OutputQuality) -> (HittableList, Camera)
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
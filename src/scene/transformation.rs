@@ -4,8 +4,9 @@ use crate::{
     camera::{Camera, CameraOption, OutputQuality},
     material::Lambertian,
     object::{HittableList, Sphere, Transformable},
-    texture::{ImageTexture, SolidColorTexture},
-    vec3::{Color, Point, Vec3},
+    shutter::Shutter,
+    texture::SolidColorTexture,
+    vec3::{Color, Matrix4, Point, Vec3},
 };
 
 pub fn construct_transformation_debug_scene(quality: OutputQuality) -> (HittableList, Camera) {
@@ -52,6 +53,32 @@ pub fn construct_transformation_debug_scene(quality: OutputQuality) -> (Hittable
         .rotate_y(60.0),
     );
 
+    let mat_green = Arc::new(Lambertian::new_solid_color(Color::GREEN));
+    world.add(
+        Sphere::stationary(Point::new(0.0, 0.0, 0.0), 1.5, mat_green)
+            .scale(Vec3::new(1.0, 0.5, 2.0))
+            .rotate_x(30.0)
+            .translate(Vec3::new(6.0, 1.5, 4.0)),
+    );
+
+    let mat_teal = Arc::new(Lambertian::new_solid_color(Color::TEAL));
+    let shear = Matrix4::new([
+        [1.0, 0.6, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+    world.add(
+        HittableList::rectangular_box(
+            &Vec3::new(-1.0, 0.0, -1.0),
+            &Vec3::new(1.0, 3.0, 1.0),
+            mat_teal,
+        )
+        .transform(shear)
+        .rotate_z(-20.0)
+        .translate(Vec3::new(2.0, 0.5, 8.0)),
+    );
+
     let camera: Camera = Camera::new(CameraOption {
         bg_color: Color::new(0.7, 0.8, 1.0),
         vfov: 30.0,
@@ -61,6 +88,7 @@ pub fn construct_transformation_debug_scene(quality: OutputQuality) -> (Hittable
         defocus_angle: 0.0,
         focus_distance: 10.0,
         quality,
+        shutter: Shutter::default(),
     });
 
     (world, camera)
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use crate::{
+    camera::{Camera, CameraOption, OutputQuality},
+    material::{Lambertian, Metal},
+    object::{HittableList, MeshBuffers, MeshFace, Triangle, TriangleMesh},
+    shutter::Shutter,
+    texture::CheckeredTexture,
+    utils::{NoiseFunction, Perlin},
+    vec3::{Color, Point, Vec3},
+};
+
+const GRID_SIZE: usize = 96;
+const TERRAIN_EXTENT: f64 = 12.0;
+
+/// Builds a noise heightfield as an indexed mesh, with smooth normals averaged from the adjacent faces.
+fn terrain_mesh() -> (MeshBuffers, Vec<MeshFace>) {
+    let noise = Perlin::new();
+    let mut buffers = MeshBuffers::default();
+    let step = 2.0 * TERRAIN_EXTENT / GRID_SIZE as f64;
+
+    for j in 0..=GRID_SIZE {
+        for i in 0..=GRID_SIZE {
+            let x = -TERRAIN_EXTENT + i as f64 * step;
+            let z = -TERRAIN_EXTENT + j as f64 * step;
+            let y = 1.5 * noise.noise(&Point::new(x * 0.2, 0.0, z * 0.2));
+            buffers.positions.push(Point::new(x, y, z));
+            buffers
+                .uvs
+                .push((i as f64 / GRID_SIZE as f64, j as f64 / GRID_SIZE as f64));
+        }
+    }
+
+    let vertex = |i: usize, j: usize| j * (GRID_SIZE + 1) + i;
+    let mut faces = Vec::with_capacity(GRID_SIZE * GRID_SIZE * 2);
+    for j in 0..GRID_SIZE {
+        for i in 0..GRID_SIZE {
+            let quad = [
+                vertex(i, j),
+                vertex(i + 1, j),
+                vertex(i + 1, j + 1),
+                vertex(i, j + 1),
+            ];
+            for indices in [[quad[0], quad[2], quad[1]], [quad[0], quad[3], quad[2]]] {
+                faces.push(MeshFace {
+                    positions: indices,
+                    normals: Some(indices),
+                    uvs: Some(indices),
+                });
+            }
+        }
+    }
+
+    buffers.normals = vec![Vec3::ZERO; buffers.positions.len()];
+    for face in &faces {
+        let [a, b, c] = face.positions.map(|i| buffers.positions[i]);
+        let face_normal = (b - a).cross(&(c - a));
+        for i in face.positions {
+            buffers.normals[i] += face_normal;
+        }
+    }
+    for normal in buffers.normals.iter_mut() {
+        *normal = normal.to_unit();
+    }
+
+    (buffers, faces)
+}
+
+pub fn construct_triangles_scene(quality: OutputQuality) -> (HittableList, Camera) {
+    let mut world = HittableList::empty();
+
+    let mat_terrain = Arc::new(Lambertian::new(Arc::new(
+        CheckeredTexture::new_from_colors(0.05, Color::new(0.2, 0.3, 0.1), Color::all(0.9)),
+    )));
+    let (buffers, faces) = terrain_mesh();
+    let terrain = TriangleMesh::new(Arc::new(buffers), &faces, mat_terrain)
+        .expect("terrain faces index the generated vertices");
+    world.add(terrain);
+
+    let mat_red = Arc::new(Lambertian::new_solid_color(Color::RED));
+    world.add(Triangle::new(
+        [
+            Point::new(-4.0, 2.0, -2.0),
+            Point::new(-1.0, 2.0, -3.0),
+            Point::new(-2.5, 5.0, -2.5),
+        ],
+        mat_red,
+    ));
+
+    // a single triangle with diverging vertex normals looks curved under a mirror finish
+    let mat_metal = Arc::new(Metal::new(Color::new(0.8, 0.8, 0.9), 0.0));
+    world.add(Triangle::with_attributes(
+        [
+            Point::new(1.0, 2.0, -3.0),
+            Point::new(4.0, 2.0, -2.0),
+            Point::new(2.5, 5.0, -2.5),
+        ],
+        Some([
+            Vec3::new(-0.5, -0.3, 1.0).to_unit(),
+            Vec3::new(0.5, -0.3, 1.0).to_unit(),
+            Vec3::new(0.0, 0.6, 1.0).to_unit(),
+        ]),
+        Some([(0.0, 0.0), (1.0, 0.0), (0.5, 1.0)]),
+        mat_metal,
+    ));
+
+    let camera: Camera = Camera::new(CameraOption {
+        bg_color: Color::new(0.7, 0.8, 1.0),
+        vfov: 40.0,
+        look_from: Point::new(0.0, 7.0, 14.0),
+        look_at: Point::new(0.0, 1.5, 0.0),
+        vup: Vec3::new(0.0, 1.0, 0.0),
+        defocus_angle: 0.0,
+        focus_distance: 10.0,
+        quality,
+        shutter: Shutter::default(),
+    });
+
+    (world, camera)
+}
@@ -0,0 +1,158 @@
+/// How much of the light arriving during the exposure reaches the film at each moment, as a
+/// fraction of the exposure time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShutterCurve {
+    /// Fully open for the whole exposure.
+    Box,
+    /// Opens linearly until the middle of the exposure, then closes linearly.
+    Triangle,
+    /// Piecewise-linear opening through values at evenly spaced times, see
+    /// `ShutterCurve::custom`.
+    Custom(TabulatedCurve),
+}
+
+/// A non-negative piecewise-linear curve with its running integral, for sampling.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TabulatedCurve {
+    values: Vec<f64>,
+    /// Integral of the curve up to each value, normalised so that the last one is 1
+    cdf: Vec<f64>,
+}
+
+impl ShutterCurve {
+    /// A curve through `values`, spaced evenly from the opening to the closing of the
+    /// shutter. Needs at least two non-negative values, not all zero.
+    pub fn custom(values: Vec<f64>) -> Result<Self, String> {
+        if values.len() < 2 {
+            return Err("a custom shutter curve needs at least two values".to_string());
+        }
+        if values.iter().any(|&v| v < 0.0 || !v.is_finite()) {
+            return Err("shutter curve values must be finite and non-negative".to_string());
+        }
+
+        let mut cdf = Vec::with_capacity(values.len());
+        let mut area = 0.0;
+        cdf.push(0.0);
+        for pair in values.windows(2) {
+            area += 0.5 * (pair[0] + pair[1]);
+            cdf.push(area);
+        }
+        if area == 0.0 {
+            return Err("a shutter curve must open at some point".to_string());
+        }
+        for value in &mut cdf {
+            *value /= area;
+        }
+        Ok(ShutterCurve::Custom(TabulatedCurve { values, cdf }))
+    }
+
+    /// Maps a uniform `u` in [0, 1) to a fraction of the exposure, distributed like the curve.
+    pub fn sample(&self, u: f64) -> f64 {
+        match self {
+            ShutterCurve::Box => u,
+            ShutterCurve::Triangle => {
+                if u < 0.5 {
+                    (u / 2.0).sqrt()
+                } else {
+                    1.0 - ((1.0 - u) / 2.0).sqrt()
+                }
+            }
+            ShutterCurve::Custom(curve) => curve.sample(u),
+        }
+    }
+}
+
+impl TabulatedCurve {
+    fn sample(&self, u: f64) -> f64 {
+        let segments = self.values.len() - 1;
+        // last segment whose start lies at or below `u`
+        let segment = self.cdf[1..].partition_point(|&c| c <= u).min(segments - 1);
+        let segment_area = self.cdf[segment + 1] - self.cdf[segment];
+        let width = 1.0 / segments as f64;
+        let (a, b) = (self.values[segment], self.values[segment + 1]);
+
+        // solve a x + (b - a) x^2 / 2 = target for the position x in [0, 1] within the segment,
+        // with the area measured in units of the segment's average height
+        let target = (u - self.cdf[segment]) / segment_area * 0.5 * (a + b);
+        let slope = b - a;
+        let x = if slope.abs() < 1e-12 {
+            target / a
+        } else {
+            (-a + (a * a + 2.0 * slope * target).max(0.0).sqrt()) / slope
+        };
+        (segment as f64 + x.clamp(0.0, 1.0)) * width
+    }
+}
+
+/// When the camera collects light. Scene motion is defined over times 0 to 1 and objects hold
+/// their first or last pose outside it, so the shutter should open at or after 0, and close by
+/// 1 in the last row, after `rolling_readout`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shutter {
+    pub open: f64,
+    pub close: f64,
+    pub curve: ShutterCurve,
+    /// Delay between the first and the last image row opening, for a rolling shutter that
+    /// reads the sensor out from top to bottom; 0 exposes every row at once.
+    pub rolling_readout: f64,
+}
+
+impl Default for Shutter {
+    fn default() -> Self {
+        Self {
+            open: 0.0,
+            close: 1.0,
+            curve: ShutterCurve::Box,
+            rolling_readout: 0.0,
+        }
+    }
+}
+
+impl Shutter {
+    /// Time of a sample taken by image row `row` of `height`, from a uniform `u` in [0, 1).
+    pub fn sample_time(&self, u: f64, row: u32, height: u32) -> f64 {
+        let delay = if height > 1 {
+            self.rolling_readout * row as f64 / (height - 1) as f64
+        } else {
+            0.0
+        };
+        self.open + delay + (self.close - self.open) * self.curve.sample(u)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Shutter, ShutterCurve};
+
+    /// Fraction of `count` stratified samples that land in the first quarter of the exposure.
+    fn first_quarter(curve: &ShutterCurve, count: u32) -> f64 {
+        let samples = (0..count).map(|i| curve.sample((i as f64 + 0.5) / count as f64));
+        samples.filter(|&t| t < 0.25).count() as f64 / count as f64
+    }
+
+    #[test]
+    fn curves_distribute_time_like_their_shape() {
+        assert!((first_quarter(&ShutterCurve::Box, 1000) - 0.25).abs() < 0.01);
+        // a triangle holds 1/8 of its area in its first quarter
+        assert!((first_quarter(&ShutterCurve::Triangle, 1000) - 0.125).abs() < 0.01);
+        let tabulated = ShutterCurve::custom(vec![0.0, 1.0, 0.0]).unwrap();
+        assert!((first_quarter(&tabulated, 1000) - 0.125).abs() < 0.01);
+        let ramp = ShutterCurve::custom(vec![1.0, 1.0, 0.0, 0.0]).unwrap();
+        assert!(ramp.sample(0.999) <= 2.0 / 3.0);
+        assert!(ShutterCurve::custom(vec![0.0, 0.0]).is_err());
+        assert!(ShutterCurve::custom(vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn rolling_shutter_delays_lower_rows() {
+        let shutter = Shutter {
+            open: 0.2,
+            close: 0.4,
+            curve: ShutterCurve::Box,
+            rolling_readout: 0.5,
+        };
+        assert_eq!(shutter.sample_time(0.0, 0, 11), 0.2);
+        assert!((shutter.sample_time(0.0, 10, 11) - 0.7).abs() < 1e-12);
+        assert!((shutter.sample_time(0.5, 5, 11) - 0.55).abs() < 1e-12);
+    }
+}
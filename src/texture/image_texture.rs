@@ -1,8 +1,9 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
-use image::{ImageReader, RgbImage};
+use image::{ImageReader, ImageResult, Rgb, Rgb32FImage};
 
 use crate::{
+    color::TransferFunction,
     interval::Interval,
     vec3::{Color, Point},
 };
@@ -10,18 +11,34 @@ use crate::{
 use super::Texture;
 
 pub struct ImageTexture {
-    img: Arc<RgbImage>,
+    img: Arc<Rgb32FImage>,
 }
 
 impl ImageTexture {
     pub fn new(img_path: &str) -> Self {
-        let res = ImageReader::open(img_path)
-            .expect("unable to read image at path")
-            .decode()
-            .expect("unable to parse image");
+        Self::load(Path::new(img_path), TransferFunction::Srgb)
+            .expect("unable to load image at path")
+    }
+
+    /// Loads an 8-bit image and decodes it to linear values with `transfer`. Color maps are
+    /// usually sRGB, while data textures such as normal maps should use `Linear`.
+    pub fn load(img_path: &Path, transfer: TransferFunction) -> ImageResult<Self> {
+        let res = ImageReader::open(img_path)?.decode()?;
         let res = res.to_rgb8();
 
-        Self { img: Arc::new(res) }
+        let lookup: Vec<f32> = (0..=255)
+            .map(|value| transfer.decode(value as f64 / 255.0) as f32)
+            .collect();
+        let linear = Rgb32FImage::from_fn(res.width(), res.height(), |x, y| {
+            Rgb(res
+                .get_pixel(x, y)
+                .0
+                .map(|channel| lookup[channel as usize]))
+        });
+
+        Ok(Self {
+            img: Arc::new(linear),
+        })
     }
 }
 
@@ -40,11 +57,6 @@ impl Texture for ImageTexture {
 
         let pixel = img.get_pixel(i, j);
 
-        let color_scale = 1.0 / 255.0;
-        Color::new(
-            pixel[0] as f64 * color_scale,
-            pixel[1] as f64 * color_scale,
-            pixel[2] as f64 * color_scale,
-        )
+        Color::new(pixel[0] as f64, pixel[1] as f64, pixel[2] as f64)
     }
 }
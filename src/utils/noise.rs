@@ -28,6 +28,12 @@ pub trait NoiseFunction {
     }
 }
 
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Perlin {
     pub fn new() -> Self {
         let mut rand_vec = [Vec3::ZERO; POINT_COUNT];
@@ -1,12 +1,37 @@
+use std::cell::RefCell;
+
 use rand::{
     distributions::uniform::{SampleRange, SampleUniform},
-    Rng,
+    rngs::SmallRng,
+    Rng, SeedableRng,
 };
 
-use crate::vec3::Vec3;
+thread_local! {
+    static RNG: RefCell<SmallRng> = RefCell::new(SmallRng::seed_from_u64(0));
+}
+
+/// Restarts the current thread's random stream at a point determined only by `seed` and
+/// `keys`, so results do not depend on which thread does the work or in what order.
+pub fn reseed(seed: u64, keys: &[u64]) {
+    let state = seeded_hash(seed, keys);
+    RNG.with(|rng| *rng.borrow_mut() = SmallRng::seed_from_u64(state));
+}
+
+/// Hash of `seed` and `keys`, for deterministic per-pixel or per-dimension values.
+pub fn seeded_hash(seed: u64, keys: &[u64]) -> u64 {
+    keys.iter()
+        .fold(splitmix64(seed), |state, &key| splitmix64(state ^ key))
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
 
 pub fn rand_double() -> f64 {
-    rand::thread_rng().gen_range(0.0..1.0)
+    RNG.with(|rng| rng.borrow_mut().gen_range(0.0..1.0))
 }
 
 pub fn rand_range<T, R>(range: R) -> T
@@ -14,21 +39,5 @@ where
     T: SampleUniform,
     R: SampleRange<T>,
 {
-    rand::thread_rng().gen_range(range)
-}
-
-pub fn rand_vector_in_unit_disk() -> Vec3 {
-    loop {
-        let v = Vec3::new(rand_range(-1.0..1.0), rand_range(-1.0..1.0), 0.0);
-        if v.length_squared() < 1.0 {
-            return v;
-        }
-    }
-}
-
-pub fn linear_to_gamma(linear: f64) -> f64 {
-    if linear > 0.0 {
-        return linear.sqrt();
-    }
-    0.0
+    RNG.with(|rng| rng.borrow_mut().gen_range(range))
 }
@@ -0,0 +1,190 @@
+use std::ops::Mul;
+
+use super::{point::Point, vec3::Vec3};
+
+/// Row-major 4x4 matrix for affine transforms of column vectors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Matrix4 {
+    pub const IDENTITY: Matrix4 = Matrix4 {
+        m: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    pub const fn new(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn translation(offset: Vec3) -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scaling(factors: Vec3) -> Self {
+        Self::new([
+            [factors.x, 0.0, 0.0, 0.0],
+            [0.0, factors.y, 0.0, 0.0],
+            [0.0, 0.0, factors.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Counter-clockwise rotation by `angle` degrees about `axis`, looking down the axis
+    /// towards the origin.
+    pub fn rotation(axis: Vec3, angle: f64) -> Self {
+        let Vec3 { x, y, z } = axis.to_unit();
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let t = 1.0 - cos;
+        Self::new([
+            [
+                t * x * x + cos,
+                t * x * y - sin * z,
+                t * x * z + sin * y,
+                0.0,
+            ],
+            [
+                t * x * y + sin * z,
+                t * y * y + cos,
+                t * y * z - sin * x,
+                0.0,
+            ],
+            [
+                t * x * z - sin * y,
+                t * y * z + sin * x,
+                t * z * z + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotation_x(angle: f64) -> Self {
+        Self::rotation(Vec3::new(1.0, 0.0, 0.0), angle)
+    }
+
+    pub fn rotation_y(angle: f64) -> Self {
+        Self::rotation(Vec3::new(0.0, 1.0, 0.0), angle)
+    }
+
+    pub fn rotation_z(angle: f64) -> Self {
+        Self::rotation(Vec3::new(0.0, 0.0, 1.0), angle)
+    }
+
+    /// Whether the last row is `[0, 0, 0, 1]`, which `transform_point` assumes.
+    pub fn is_affine(&self) -> bool {
+        self.m[3] == [0.0, 0.0, 0.0, 1.0]
+    }
+
+    /// Determinant of the linear (upper-left 3x3) part.
+    pub fn linear_determinant(&self) -> f64 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Self::new(m)
+    }
+
+    /// Gauss-Jordan elimination with partial pivoting; `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Self::IDENTITY.m;
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for j in 0..4 {
+                a[col][j] *= scale;
+                inv[col][j] *= scale;
+            }
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = a[row][col];
+                for j in 0..4 {
+                    a[row][j] -= factor * a[col][j];
+                    inv[row][j] -= factor * inv[col][j];
+                }
+            }
+        }
+        Some(Self::new(inv))
+    }
+
+    pub fn transform_point(&self, p: &Point) -> Point {
+        self.transform_vector(p) + Vec3::new(self.m[0][3], self.m[1][3], self.m[2][3])
+    }
+
+    /// Applies the linear part only, ignoring translation.
+    pub fn transform_vector(&self, v: &Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Matrix4;
+
+    /// `a * b` applies `b` first, then `a`.
+    fn mul(self, rhs: Matrix4) -> Matrix4 {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Matrix4::new(m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vec3::Vec3;
+
+    use super::Matrix4;
+
+    #[test]
+    fn inverse_undoes_an_affine_transform() {
+        let matrix = Matrix4::translation(Vec3::new(1.0, -2.0, 3.0))
+            * Matrix4::rotation(Vec3::new(1.0, 1.0, 0.0), 30.0)
+            * Matrix4::scaling(Vec3::new(2.0, 0.5, 3.0));
+        let inverse = matrix.inverse().unwrap();
+        let p = Vec3::new(0.3, 0.7, -1.1);
+        let back = inverse.transform_point(&matrix.transform_point(&p));
+        assert!((back - p).length() < 1e-12);
+
+        let rotated = Matrix4::rotation_z(90.0).transform_vector(&Vec3::new(1.0, 0.0, 0.0));
+        assert!((rotated - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-12);
+        assert!(Matrix4::scaling(Vec3::new(1.0, 0.0, 1.0))
+            .inverse()
+            .is_none());
+    }
+}
@@ -1,7 +1,14 @@
-mod vec3;
 mod color;
+mod matrix;
+mod onb;
 mod point;
+mod quaternion;
+#[allow(clippy::module_inception)]
+mod vec3;
 
-pub use vec3::Vec3;
 pub use color::Color;
-pub use point::Point;
\ No newline at end of file
+pub use matrix::Matrix4;
+pub use onb::Onb;
+pub use point::Point;
+pub use quaternion::Quaternion;
+pub use vec3::Vec3;
@@ -0,0 +1,27 @@
+use super::vec3::Vec3;
+
+/// Orthonormal basis whose `w` axis is aligned with a given direction.
+pub struct Onb {
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+}
+
+impl Onb {
+    pub fn new(n: &Vec3) -> Self {
+        let w = n.to_unit();
+        let a = if w.x.abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = w.cross(&a).to_unit();
+        let u = w.cross(&v);
+        Self { u, v, w }
+    }
+
+    /// Maps a vector expressed in this basis back to world space.
+    pub fn transform(&self, a: &Vec3) -> Vec3 {
+        a.x * self.u + a.y * self.v + a.z * self.w
+    }
+}
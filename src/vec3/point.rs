@@ -7,6 +7,6 @@ impl std::fmt::Display for Point {
 }
 impl Point {
     pub fn dist(&self, b: Point) -> f64 {
-        return (b - *self).length();
+        (b - *self).length()
     }
 }
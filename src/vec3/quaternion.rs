@@ -0,0 +1,166 @@
+use super::{matrix::Matrix4, vec3::Vec3};
+
+/// Unit quaternion representing a rotation, `w + xi + yj + zk`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Quaternion {
+    pub const IDENTITY: Quaternion = Quaternion {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 1.0,
+    };
+
+    /// Counter-clockwise rotation by `angle` degrees about `axis`, matching `Matrix4::rotation`.
+    pub fn from_axis_angle(axis: Vec3, angle: f64) -> Self {
+        let axis = axis.to_unit();
+        let (sin, cos) = (angle.to_radians() / 2.0).sin_cos();
+        Self {
+            x: axis.x * sin,
+            y: axis.y * sin,
+            z: axis.z * sin,
+            w: cos,
+        }
+    }
+
+    /// Rotation of the upper-left 3x3 block of `m`, which must be orthonormal.
+    pub fn from_matrix(m: &Matrix4) -> Self {
+        let m = &m.m;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        let q = if trace > 0.0 {
+            let s = 2.0 * (trace + 1.0).sqrt();
+            Self {
+                x: (m[2][1] - m[1][2]) / s,
+                y: (m[0][2] - m[2][0]) / s,
+                z: (m[1][0] - m[0][1]) / s,
+                w: s / 4.0,
+            }
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = 2.0 * (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt();
+            Self {
+                x: s / 4.0,
+                y: (m[0][1] + m[1][0]) / s,
+                z: (m[0][2] + m[2][0]) / s,
+                w: (m[2][1] - m[1][2]) / s,
+            }
+        } else if m[1][1] > m[2][2] {
+            let s = 2.0 * (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt();
+            Self {
+                x: (m[0][1] + m[1][0]) / s,
+                y: s / 4.0,
+                z: (m[1][2] + m[2][1]) / s,
+                w: (m[0][2] - m[2][0]) / s,
+            }
+        } else {
+            let s = 2.0 * (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt();
+            Self {
+                x: (m[0][2] + m[2][0]) / s,
+                y: (m[1][2] + m[2][1]) / s,
+                z: s / 4.0,
+                w: (m[1][0] - m[0][1]) / s,
+            }
+        };
+        q.normalized()
+    }
+
+    pub fn to_matrix(&self) -> Matrix4 {
+        let Self { x, y, z, w } = *self;
+        Matrix4::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// The opposite rotation, for a unit quaternion.
+    pub fn conjugate(&self) -> Self {
+        Self {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: self.w,
+        }
+    }
+
+    /// Rotates `v`, like `to_matrix().transform_vector(v)` without building the matrix.
+    pub fn rotate(&self, v: &Vec3) -> Vec3 {
+        let axis = Vec3::new(self.x, self.y, self.z);
+        let t = 2.0 * Vec3::cross(&axis, v);
+        *v + self.w * t + Vec3::cross(&axis, &t)
+    }
+
+    pub fn dot(&self, other: &Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn normalized(&self) -> Self {
+        let length = self.dot(self).sqrt();
+        Self {
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+            w: self.w / length,
+        }
+    }
+
+    /// Angle in radians of the shortest rotation taking `self` to `other`.
+    pub fn angle_to(&self, other: &Self) -> f64 {
+        2.0 * self.dot(other).abs().min(1.0).acos()
+    }
+
+    /// Spherical linear interpolation along the shorter arc, at constant angular speed.
+    pub fn slerp(&self, other: &Self, t: f64) -> Self {
+        let mut cos_theta = self.dot(other);
+        let mut other = *other;
+        if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            other = Self {
+                x: -other.x,
+                y: -other.y,
+                z: -other.z,
+                w: -other.w,
+            };
+        }
+
+        let (a, b) = if cos_theta > 0.9995 {
+            // nearly parallel, where normalised linear interpolation is accurate and stable
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+        Self {
+            x: a * self.x + b * other.x,
+            y: a * self.y + b * other.y,
+            z: a * self.z + b * other.z,
+            w: a * self.w + b * other.w,
+        }
+        .normalized()
+    }
+}
@@ -80,8 +80,8 @@ impl Vec3 {
         self.x.abs() < EPS && self.y.abs() < EPS && self.y.abs() < EPS
     }
 
-    pub fn to_unit(&self) -> Vec3 {
-        *self / self.length()
+    pub fn to_unit(self) -> Vec3 {
+        self / self.length()
     }
 
     pub fn reflect(&self, normal: &Vec3) -> Vec3 {